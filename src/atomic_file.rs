@@ -0,0 +1,28 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes the file via a uniquely named temporary file in the same directory that is renamed over the target, so
+/// readers and concurrent writers only ever see a complete file.
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<(), io::Error> {
+    let mut temp_file_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_file_name.push(format!(
+        ".{}.{}.tmp",
+        process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_path = path.with_file_name(temp_file_name);
+
+    fs::write(&temp_path, contents)?;
+
+    if let Err(e) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    Ok(())
+}
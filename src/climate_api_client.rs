@@ -1,15 +1,93 @@
-use crate::error::Error;
-use reqwest::{self};
+use crate::atomic_file::write_atomically;
+use crate::countries::{Country, COUNTRIES};
+use crate::disk_cache::DiskCache;
+use crate::error::{Error, ValidationError};
+use crate::header_provider::{HeaderProvider, HeaderProviderFn};
+use crate::jitter::Jitter;
+use crate::markdown::{self, Interaction};
+use crate::rainfall::Rainfall;
+use crate::rainfall_class::RainfallClass;
+use crate::request_hook::{RequestHook, RequestHookFn, RequestInfo};
+use crate::token_bucket::TokenBucket;
+use crate::unsupported_range::{UnsupportedRange, UnsupportedRangeFn};
+use quick_xml::{events::Event, DeError, Reader};
+use reqwest::{
+    self,
+    header::{
+        HeaderMap, HeaderValue, ACCEPT, ETAG, HOST, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+    },
+    redirect::Policy,
+    Certificate, StatusCode,
+};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::net::SocketAddr;
+use std::num::ParseFloatError;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 type ReqwestClient = reqwest::blocking::Client;
-use crate::data::annual_gcm_data::AnnualGcmData;
+type ReqwestRequest = reqwest::blocking::Request;
+type ReqwestResponse = reqwest::blocking::Response;
+use crate::data::annual_ensemble_data::AnnualEnsembleData;
+use crate::data::annual_gcm_data::{AnnualGcmData, AnnualGcmDatum};
+use crate::data::combined_climate_data::CombinedClimateData;
+use crate::data::monthly_gcm_data::MonthlyGcmData;
+use crate::year_window::{WindowKind, YearWindow};
+use serde_json::json;
 
 const DEFAULT_DOMAIN_NAME: &str = "https://servirtium.github.io/worldbank-climate-recordings";
+const SERVIRTIUM_DEFAULT_PORT: u16 = 61417;
+const RAINFALL_VARIABLE: &str = "pr";
+const ANNUAL_AVERAGE_TYPE: &str = "annualavg";
+const ANNUAL_AVERAGE_ENSEMBLE_TYPE: &str = "annualavg/ensemble";
+const MONTHLY_ANOMALY_TYPE: &str = "manom";
+const PING_FROM_YEAR: u16 = 1980;
+const PING_TO_YEAR: u16 = 1999;
+const PING_COUNTRY_ISO: &str = "gbr";
+/// Element names of the value inside `annualData`, matching the aliases accepted by `AnnualData`.
+const ANNUAL_VALUE_ELEMENTS: [&[u8]; 5] = [b"double", b"float", b"int", b"long", b"big-decimal"];
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+const BATCH_CONCURRENCY: usize = 4;
 
 /// Builder used to build a ClimateApiClient instance
-#[derive(Debug, Clone, Default)]
+///
+/// # Example
+/// ```
+/// use worldbank_climate_api_client::ClimateApiClientBuilder;
+///
+/// let climate_api = ClimateApiClientBuilder::new()
+///     .with_domain_name("http://localhost:61417")
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
 pub struct ClimateApiClientBuilder {
     domain_name: Option<String>,
+    fallback_domain_name: Option<String>,
     http_client: Option<ReqwestClient>,
+    redirect_policy: Option<Arc<Policy>>,
+    http2_prior_knowledge: bool,
+    etag_cache: bool,
+    last_modified_cache: bool,
+    request_hook: Option<RequestHook>,
+    default_headers: Option<HeaderMap>,
+    header_provider: Option<HeaderProvider>,
+    unsupported_range: Option<UnsupportedRange>,
+    rate_limit: Option<f64>,
+    max_retries: u32,
+    retry_budget: Option<(u32, f64)>,
+    retry_jitter: f64,
+    retry_jitter_seed: Option<u64>,
+    country_error_detection: CountryErrorDetection,
+    disk_cache_directory: Option<PathBuf>,
+    disk_cache_ttl: Option<Duration>,
+    danger_accept_invalid_certs: bool,
+    root_certificates: Vec<Certificate>,
+    resolve_overrides: Vec<(String, SocketAddr)>,
+    default_window: Option<YearWindow>,
 }
 
 impl ClimateApiClientBuilder {
@@ -17,11 +95,46 @@ impl ClimateApiClientBuilder {
     pub fn new() -> Self {
         Self {
             domain_name: None,
+            fallback_domain_name: None,
             http_client: None,
+            redirect_policy: None,
+            http2_prior_knowledge: false,
+            etag_cache: false,
+            last_modified_cache: false,
+            request_hook: None,
+            default_headers: None,
+            header_provider: None,
+            unsupported_range: None,
+            rate_limit: None,
+            max_retries: 0,
+            retry_budget: None,
+            retry_jitter: 1.0,
+            retry_jitter_seed: None,
+            country_error_detection: CountryErrorDetection::default(),
+            disk_cache_directory: None,
+            disk_cache_ttl: None,
+            danger_accept_invalid_certs: false,
+            root_certificates: Vec::new(),
+            resolve_overrides: Vec::new(),
+            default_window: None,
         }
     }
 
-    /// Use the given domain_name when building a ClimateApiClient instance.
+    /// Create a ClimateApiClientBuilder pointing at a Servirtium server running locally.
+    ///
+    /// # Arguments
+    /// `port` - port the Servirtium server listens on.
+    ///
+    /// # Returns
+    /// A builder with the domain name set to `http://localhost:<port>`.
+    pub fn servirtium_local(port: u16) -> Self {
+        Self::new().with_domain_name(format!("http://localhost:{}", port))
+    }
+
+    /// Use the given domain_name when building a ClimateApiClient instance. The domain name is an origin, e.g.
+    /// `http://localhost:61417`, optionally followed by a path prefix, e.g. `http://host/mirror`. The API path
+    /// (`/climateweb/rest/v1/...`) is appended after the prefix; trailing slashes are removed so that
+    /// `http://host/mirror/` behaves the same as `http://host/mirror`.
     ///
     /// # Arguments
     /// `domain_name` - a domain name to use when calling the API.
@@ -33,6 +146,21 @@ impl ClimateApiClientBuilder {
         self
     }
 
+    /// Use the given fallback domain name when a request to the primary domain fails at the transport level (e.g. the
+    /// connection is refused or times out). Requests that fail with an error status code or that can't be parsed are
+    /// not retried.
+    ///
+    /// # Arguments
+    /// `domain_name` - a domain name to use when the primary domain is unreachable. It is normalized the same way as
+    ///     in `with_domain_name`.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_fallback_domain<T: Into<String>>(mut self, domain_name: T) -> Self {
+        self.fallback_domain_name = Some(domain_name.into());
+        self
+    }
+
     /// Use the given blocking reqwest client when building a ClimateApiClient instance.
     ///
     /// # Arguments
@@ -45,27 +173,469 @@ impl ClimateApiClientBuilder {
         self
     }
 
+    /// Use the given redirect policy for the default reqwest client. Ignored if a custom client is configured with
+    /// `with_http_client`.
+    ///
+    /// # Arguments
+    /// `policy` - a redirect policy, by default reqwest follows up to 10 redirects.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_redirect_policy(mut self, policy: Policy) -> Self {
+        self.redirect_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Make the default reqwest client talk HTTP/2 without negotiating it first. Only enable this for endpoints that
+    /// are known to support HTTP/2. Ignored if a custom client is configured with `with_http_client`.
+    ///
+    /// # Arguments
+    /// `enabled` - whether to use HTTP/2 with prior knowledge.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Cache responses that carry an `ETag` in memory and revalidate them with `If-None-Match`, serving the cached
+    /// body when the API answers `304 Not Modified`. Entries are keyed by the full URL, domain and path, so responses
+    /// of the fallback domain never revalidate those of the primary one. Clones of the built client share the cache.
+    ///
+    /// # Arguments
+    /// `enabled` - whether to use the ETag cache.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_etag_cache(mut self, enabled: bool) -> Self {
+        self.etag_cache = enabled;
+        self
+    }
+
+    /// Cache responses that carry a `Last-Modified` date in memory and revalidate them with `If-Modified-Since`,
+    /// serving the cached body when the API answers `304 Not Modified`. Can be combined with `with_etag_cache`, in
+    /// which case both validators are sent. Clones of the built client share the cache.
+    ///
+    /// # Arguments
+    /// `enabled` - whether to use the Last-Modified cache.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_last_modified_cache(mut self, enabled: bool) -> Self {
+        self.last_modified_cache = enabled;
+        self
+    }
+
+    /// Call the given hook before each request with its URL and after each response with its status and duration,
+    /// e.g. to create tracing spans or record metrics.
+    ///
+    /// # Arguments
+    /// `hook` - a callback that receives the request information.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_request_hook(mut self, hook: Arc<RequestHookFn>) -> Self {
+        self.request_hook = Some(RequestHook(hook));
+        self
+    }
+
+    /// Send the given headers with every request made by the default reqwest client. Calling this again replaces the
+    /// previously configured headers. Ignored if a custom client is configured with `with_http_client`, configure
+    /// its default headers instead.
+    ///
+    /// # Arguments
+    /// `headers` - headers to send with every request.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = Some(headers);
+        self
+    }
+
+    /// Throttle outgoing requests to at most `requests_per_second`, spread evenly over time. The limit applies across
+    /// all methods of the built client and its clones; separately built clients have independent limiters.
+    ///
+    /// # Arguments
+    /// `requests_per_second` - maximum request rate. A rate that isn't positive turns the limit off.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.rate_limit = Some(requests_per_second).filter(|rate| *rate > 0.0);
+        self
+    }
+
+    /// Accept any TLS certificate, including self-signed and expired ones, when using the default reqwest client.
+    /// Ignored if a custom client is configured with `with_http_client`.
+    ///
+    /// # Warning
+    /// This disables protection against man-in-the-middle attacks: anyone able to intercept the traffic can read and
+    /// alter the responses. Only enable it against trusted hosts, e.g. an internal mirror while recording fixtures, and
+    /// prefer `with_root_certificate` where possible.
+    ///
+    /// # Arguments
+    /// `enabled` - whether invalid certificates should be accepted.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_danger_accept_invalid_certs(mut self, enabled: bool) -> Self {
+        self.danger_accept_invalid_certs = enabled;
+        self
+    }
+
+    /// Trust an additional root certificate when using the default reqwest client, e.g. the certificate authority of
+    /// an internal mirror. Can be called repeatedly to add several certificates. Ignored if a custom client is
+    /// configured with `with_http_client`.
+    ///
+    /// # Arguments
+    /// `certificate` - root certificate to trust in addition to the system ones.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Retry requests that failed with a transport error or a server error (5xx) up to `max_retries` times, waiting
+    /// exponentially longer between attempts starting at up to 100 ms, shortened by the jitter configured with
+    /// `with_retry_jitter`. Requests aren't retried by default.
+    ///
+    /// # Arguments
+    /// `max_retries` - maximum number of retries of a single request.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Cap the total number of retries across all requests of the built client and its clones, e.g. over a batch, so
+    /// a degraded upstream doesn't cause a retry storm. Each retry takes a token from a bucket of `capacity` tokens
+    /// refilled at `retries_per_second`; once the bucket is empty, failed requests fail fast without retrying. Only
+    /// has an effect together with `with_max_retries`.
+    ///
+    /// # Arguments
+    /// `capacity` - maximum number of retries available at once.
+    /// `retries_per_second` - rate at which retries become available again. Zero means the budget never refills, and
+    ///     a negative or NaN rate is treated as zero.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_retry_budget(mut self, capacity: u32, retries_per_second: f64) -> Self {
+        self.retry_budget = Some((capacity, retries_per_second.max(0.0)));
+        self
+    }
+
+    /// Randomize the delays between retries so many clients failing at the same time, e.g. during an outage, don't
+    /// retry in lockstep. Each delay is shortened by a random part of up to `fraction` of its length. Defaults to
+    /// full jitter (1.0), which spreads each delay uniformly between zero and its full length; 0.0 disables jitter.
+    ///
+    /// # Arguments
+    /// `fraction` - largest part of a delay that may be cut off, between 0.0 and 1.0. Values outside of that range
+    ///     are clamped to it and NaN disables jitter.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_retry_jitter(mut self, fraction: f64) -> Self {
+        self.retry_jitter = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        self
+    }
+
+    /// Seed the random number generator of the retry jitter, so the delays between retries are reproducible, e.g. in
+    /// tests. The generator is seeded from the current time by default.
+    ///
+    /// # Arguments
+    /// `seed` - seed of the random number generator.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_retry_jitter_seed(mut self, seed: u64) -> Self {
+        self.retry_jitter_seed = Some(seed);
+        self
+    }
+
+    /// Choose how responses for unknown countries are recognized and mapped to `Error::NotRecognizedByClimateWeb`.
+    /// Defaults to `CountryErrorDetection::BodyText`; the other modes suit deployments that report unknown countries
+    /// differently.
+    ///
+    /// # Arguments
+    /// `detection` - the detection mode.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_country_error_detection(mut self, detection: CountryErrorDetection) -> Self {
+        self.country_error_detection = detection;
+        self
+    }
+
+    /// Store response bodies in the given directory and serve repeated requests from there instead of the network,
+    /// e.g. for repeatable offline analysis. Entries never expire unless a TTL is set with `with_disk_cache_ttl`.
+    /// A cache hit is answered without a request, so it skips the request hook and doesn't take from the rate limit.
+    ///
+    /// # Arguments
+    /// `directory` - directory for the cached responses. It is created on the first write.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_disk_cache(mut self, directory: PathBuf) -> Self {
+        self.disk_cache_directory = Some(directory);
+        self
+    }
+
+    /// Ignore disk cache entries older than `ttl`, fetching them from the network again. Only has an effect together
+    /// with `with_disk_cache`.
+    ///
+    /// # Arguments
+    /// `ttl` - maximum age of a cached response.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_disk_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.disk_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Add headers produced by `provider` to every request, e.g. a fresh bearer token. Unlike `with_default_headers`
+    /// the provider is invoked before each request, including retries, and works with a custom client too.
+    ///
+    /// # Arguments
+    /// `provider` - callback producing `(name, value)` header pairs. It may be called from several threads at once.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_header_provider(mut self, provider: Arc<HeaderProviderFn>) -> Self {
+        self.header_provider = Some(HeaderProvider(provider));
+        self
+    }
+
+    /// Recognize responses meaning that the requested year interval isn't supported, e.g. a message in the body or a
+    /// specific status code, and report them as `Error::DateRangeNotSupported`. Applies to the annual average
+    /// endpoint in addition to the default check for a response without per-GCM results.
+    ///
+    /// # Arguments
+    /// `predicate` - callback getting the response body and status code. It may be called from several threads at
+    ///     once.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_unsupported_range_predicate(mut self, predicate: Arc<UnsupportedRangeFn>) -> Self {
+        self.unsupported_range = Some(UnsupportedRange(predicate));
+        self
+    }
+
+    /// Resolve `host` to `address` instead of using DNS when using the default reqwest client, e.g. to pin requests to
+    /// a specific edge server. DNS has no notion of ports, so the port of `address` is ignored and the one of the
+    /// domain name is used. Ignored if a custom client is configured with `with_http_client`.
+    ///
+    /// # Arguments
+    /// `host` - host name to override.
+    /// `address` - address to connect to instead.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_resolve<T: Into<String>>(mut self, host: T, address: SocketAddr) -> Self {
+        self.resolve_overrides.push((host.into(), address));
+        self
+    }
+
+    /// Set the year window used by `ClimateApiClient::get_average_annual_rainfall_default`, for clients dedicated to
+    /// a fixed period. An unsupported window is already rejected by `YearWindow::new`.
+    ///
+    /// # Arguments
+    /// `window` - the default year window.
+    ///
+    /// # Returns
+    /// This builder.
+    pub fn with_default_window(mut self, window: YearWindow) -> Self {
+        self.default_window = Some(window);
+        self
+    }
+
     /// Consume the builder and create a ClimateApiClient instance using all of the previously configured values or
     /// their defaults.
     ///
     /// # Returns
     /// A ClimateApiClient instance.
     pub fn build(mut self) -> ClimateApiClient {
+        let custom_http_client = self.http_client.is_some();
+        let http = match self.http_client.take() {
+            Some(http_client) => http_client,
+            None => self.build_http_client(),
+        };
+
+        let default_headers = match &self.default_headers {
+            Some(default_headers) if !custom_http_client => default_headers.clone(),
+            _ => HeaderMap::new(),
+        };
+
         ClimateApiClient {
-            http: self.http_client.take().unwrap_or_default(),
-            domain_name: self
-                .domain_name
+            http,
+            custom_http_client,
+            default_headers,
+            domain_name: normalize_domain_name(
+                self.domain_name.as_deref().unwrap_or(DEFAULT_DOMAIN_NAME),
+            ),
+            fallback_domain_name: self
+                .fallback_domain_name
+                .as_deref()
+                .map(normalize_domain_name),
+            response_cache: if self.etag_cache || self.last_modified_cache {
+                Some(Arc::new(Mutex::new(HashMap::new())))
+            } else {
+                None
+            },
+            etag_cache: self.etag_cache,
+            last_modified_cache: self.last_modified_cache,
+            request_hook: self.request_hook.take(),
+            header_provider: self.header_provider.take(),
+            unsupported_range: self.unsupported_range.take(),
+            accepts_invalid_certs: !custom_http_client && self.danger_accept_invalid_certs,
+            rate_limiter: self
+                .rate_limit
+                .map(|requests_per_second| Arc::new(TokenBucket::new(requests_per_second, 1))),
+            max_retries: self.max_retries,
+            retry_budget: self.retry_budget.map(|(capacity, retries_per_second)| {
+                Arc::new(TokenBucket::new(retries_per_second, capacity))
+            }),
+            retry_jitter: if self.retry_jitter > 0.0 {
+                let jitter = match self.retry_jitter_seed {
+                    Some(seed) => Jitter::new(self.retry_jitter, seed),
+                    None => Jitter::unseeded(self.retry_jitter),
+                };
+                Some(Arc::new(jitter))
+            } else {
+                None
+            },
+            deadline: None,
+            country_error_detection: self.country_error_detection,
+            disk_cache: self
+                .disk_cache_directory
                 .take()
-                .unwrap_or_else(|| String::from(DEFAULT_DOMAIN_NAME)),
+                .map(|directory| DiskCache::new(directory, self.disk_cache_ttl)),
+            default_window: self.default_window,
+        }
+    }
+
+    /// Create a ClimateApiClient pointing at the given domain without consuming the builder, so a configured builder
+    /// can be used as a template for clients of several domains. Each built client gets its own ETag cache and rate
+    /// limiter, while a custom reqwest client and the request hook are shared.
+    ///
+    /// # Arguments
+    /// `domain_name` - domain name of the API for the built client, overriding the one configured on the builder.
+    ///
+    /// # Returns
+    /// A ClimateApiClient.
+    pub fn build_with_domain<T: Into<String>>(&self, domain_name: T) -> ClimateApiClient {
+        self.clone().with_domain_name(domain_name).build()
+    }
+
+    fn build_http_client(&self) -> ReqwestClient {
+        let mut builder = ReqwestClient::builder();
+
+        if let Some(redirect_policy) = &self.redirect_policy {
+            let redirect_policy = Arc::clone(redirect_policy);
+
+            builder = builder.redirect(Policy::custom(move |attempt| {
+                redirect_policy.redirect(attempt)
+            }));
+        }
+
+        if let Some(default_headers) = &self.default_headers {
+            builder = builder.default_headers(default_headers.clone());
+        }
+
+        for (host, address) in &self.resolve_overrides {
+            builder = builder.resolve(host, *address);
+        }
+
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
         }
+
+        if self.danger_accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        builder
+            .build()
+            .expect("Failed to build the default reqwest client")
+    }
+}
+
+impl Default for ClimateApiClientBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// How the client recognizes that the API doesn't know the requested country.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountryErrorDetection {
+    /// The response body starts with `Invalid country code`, which is what the API currently returns.
+    #[default]
+    BodyText,
+    /// The response body isn't an XML document of the expected shape.
+    Shape,
+    /// The response has a client error status code (4xx).
+    Status,
+}
+
 /// Struct that represents a World Bank Climate Data API client.
 #[derive(Default, Debug, Clone)]
 pub struct ClimateApiClient {
     http: ReqwestClient,
+    custom_http_client: bool,
+    default_headers: HeaderMap,
     domain_name: String,
+    fallback_domain_name: Option<String>,
+    response_cache: Option<Arc<Mutex<HashMap<String, CachedResponse>>>>,
+    etag_cache: bool,
+    last_modified_cache: bool,
+    request_hook: Option<RequestHook>,
+    header_provider: Option<HeaderProvider>,
+    unsupported_range: Option<UnsupportedRange>,
+    rate_limiter: Option<Arc<TokenBucket>>,
+    max_retries: u32,
+    retry_budget: Option<Arc<TokenBucket>>,
+    retry_jitter: Option<Arc<Jitter>>,
+    accepts_invalid_certs: bool,
+    deadline: Option<Instant>,
+    country_error_detection: CountryErrorDetection,
+    disk_cache: Option<DiskCache>,
+    default_window: Option<YearWindow>,
+}
+
+struct SentRequest<'a> {
+    domain_name: &'a str,
+    request_headers: HeaderMap,
+    response: ReqwestResponse,
+}
+
+struct FetchedResponse {
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    headers: Vec<(String, String)>,
+    body: String,
 }
 
 impl ClimateApiClient {
@@ -76,10 +646,36 @@ impl ClimateApiClient {
     pub fn new() -> Self {
         ClimateApiClient {
             http: ReqwestClient::new(),
+            custom_http_client: false,
+            default_headers: HeaderMap::new(),
             domain_name: String::from(DEFAULT_DOMAIN_NAME),
+            fallback_domain_name: None,
+            response_cache: None,
+            etag_cache: false,
+            last_modified_cache: false,
+            request_hook: None,
+            header_provider: None,
+            unsupported_range: None,
+            rate_limiter: None,
+            max_retries: 0,
+            retry_budget: None,
+            retry_jitter: None,
+            accepts_invalid_certs: false,
+            deadline: None,
+            country_error_detection: CountryErrorDetection::default(),
+            disk_cache: None,
+            default_window: None,
         }
     }
 
+    /// Create a ClimateApiClient pointing at a Servirtium server running locally on its default port (61417).
+    ///
+    /// # Returns
+    /// A ClimateApiClient.
+    pub fn for_servirtium() -> Self {
+        ClimateApiClientBuilder::servirtium_local(SERVIRTIUM_DEFAULT_PORT).build()
+    }
+
     /// Gets an average annual rainfall data from WorldBank Climate Data API.
     ///
     /// # Arguments
@@ -90,310 +686,4514 @@ impl ClimateApiClient {
     ///
     /// # Returns
     /// Average of all of the average annual values from all Global Circulation Models (GCM).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use worldbank_climate_api_client::{ClimateApiClient, Error};
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let climate_api = ClimateApiClient::new();
+    /// let rainfall = climate_api.get_average_annual_rainfall(1980, 1999, "gbr")?;
+    ///
+    /// println!("Average annual rainfall in Great Britain: {}", rainfall);
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn get_average_annual_rainfall<T: AsRef<str>>(
         &self,
         from_year: u16,
         to_year: u16,
         country_iso: T,
     ) -> Result<f64, Error> {
-        let url = self.construct_get_average_annual_rainfall_url(from_year, to_year, country_iso);
+        self.get_average_annual_value(RAINFALL_VARIABLE, from_year, to_year, country_iso)
+    }
 
-        let response_text = self.http.get(&url).send()?.error_for_status()?.text()?;
+    /// Gets an average annual value of an arbitrary climate variable from WorldBank Climate Data API. This is an escape
+    /// hatch for variables that don't have a dedicated method yet.
+    ///
+    /// # Arguments
+    /// `variable` - name of the climate variable as used in the API URL, e.g. `pr` for precipitation. The caller is
+    ///     responsible for passing a variable name that the API recognizes.
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM).
+    pub fn get_average_annual_value<T: AsRef<str>>(
+        &self,
+        variable: &str,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let data = self.get_annual_gcm_data(variable, from_year, to_year, country_iso)?;
 
-        if response_text.starts_with("Invalid country code") {
-            return Err(Error::NotRecognizedByClimateWeb);
-        }
+        Ok(average(&data))
+    }
+
+    /// Gets an average annual rainfall data from WorldBank Climate Data API together with the response headers, e.g.
+    /// to inspect `ETag` or `Cache-Control`.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) and the response headers
+    /// as lowercase name and value pairs.
+    pub fn get_average_annual_rainfall_with_headers_response<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<(f64, Vec<(String, String)>), Error> {
+        let (data, headers) = self.get_annual_gcm_data_with_headers(
+            RAINFALL_VARIABLE,
+            from_year,
+            to_year,
+            country_iso,
+        )?;
+
+        Ok((average(&data), headers))
+    }
+
+    /// Gets an average annual rainfall data from WorldBank Climate Data API and measures how long it took.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) together with the time
+    /// spent on the HTTP request and parsing the response.
+    pub fn get_average_annual_rainfall_timed<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<(f64, Duration), Error> {
+        let start = Instant::now();
+        let average = self.get_average_annual_rainfall(from_year, to_year, country_iso)?;
+
+        Ok((average, start.elapsed()))
+    }
+
+    /// Gets an average annual rainfall data from WorldBank Climate Data API for the given year window.
+    ///
+    /// # Arguments
+    /// `window` - the year interval.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM).
+    pub fn get_average_annual_rainfall_window<T: AsRef<str>>(
+        &self,
+        window: YearWindow,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        self.get_average_annual_rainfall(window.from(), window.to(), country_iso)
+    }
+
+    /// Checks that the WorldBank Climate Data API is reachable by requesting a resource that is known to exist.
+    ///
+    /// # Returns
+    /// `Ok(())` if the API responded with a success status code.
+    pub fn ping(&self) -> Result<(), Error> {
+        let path = self.construct_get_average_annual_value_path(
+            RAINFALL_VARIABLE,
+            PING_FROM_YEAR,
+            PING_TO_YEAR,
+            PING_COUNTRY_ISO,
+        );
+
+        self.send(&path, &HeaderMap::new())?;
+
+        Ok(())
+    }
+
+    /// Gets the average annual rainfall of two countries for the same year interval.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso_first` - ISO3 code of the first country.
+    /// `country_iso_second` - ISO3 code of the second country.
+    ///
+    /// # Returns
+    /// Average annual rainfall of the first and the second country. A failed request is reported as
+    /// `Error::FirstCountry` or `Error::SecondCountry` naming the country and wrapping the underlying error.
+    pub fn get_average_annual_rainfall_for_two<T1: AsRef<str>, T2: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso_first: T1,
+        country_iso_second: T2,
+    ) -> Result<(f64, f64), Error> {
+        let country_iso_first = country_iso_first.as_ref();
+        let country_iso_second = country_iso_second.as_ref();
+
+        let first = self
+            .get_average_annual_rainfall(from_year, to_year, country_iso_first)
+            .map_err(|e| Error::FirstCountry(String::from(country_iso_first), Box::new(e)))?;
+        let second = self
+            .get_average_annual_rainfall(from_year, to_year, country_iso_second)
+            .map_err(|e| Error::SecondCountry(String::from(country_iso_second), Box::new(e)))?;
+
+        Ok((first, second))
+    }
+
+    /// Checks whether the first country gets more average annual rainfall than the second one.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso_first` - ISO3 code of the country expected to be wetter.
+    /// `country_iso_second` - ISO3 code of the country to compare with.
+    ///
+    /// # Returns
+    /// `true` if the average annual rainfall of the first country is greater than that of the second one.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use worldbank_climate_api_client::{ClimateApiClient, Error};
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let climate_api = ClimateApiClient::new();
+    ///
+    /// if climate_api.is_wetter(1980, 1999, "gbr", "egy")? {
+    ///     println!("Great Britain is wetter than Egypt");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_wetter<T1: AsRef<str>, T2: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso_first: T1,
+        country_iso_second: T2,
+    ) -> Result<bool, Error> {
+        let (first, second) = self.get_average_annual_rainfall_for_two(
+            from_year,
+            to_year,
+            country_iso_first,
+            country_iso_second,
+        )?;
+
+        Ok(first > second)
+    }
+
+    /// Gets the percentage change of the average annual rainfall between two year windows.
+    ///
+    /// # Arguments
+    /// `country_iso` - ISO3 country code
+    /// `from_window` - the base year interval.
+    /// `to_window` - the year interval to compare with the base.
+    ///
+    /// # Returns
+    /// Percentage change of the average annual rainfall from `from_window` to `to_window`, or
+    /// `Error::ZeroAverage` if the average for `from_window` is zero.
+    pub fn get_rainfall_change_pct<T: AsRef<str>>(
+        &self,
+        country_iso: T,
+        from_window: YearWindow,
+        to_window: YearWindow,
+    ) -> Result<f64, Error> {
+        let base = self.get_average_annual_rainfall_window(from_window, country_iso.as_ref())?;
+        let compared = self.get_average_annual_rainfall_window(to_window, country_iso.as_ref())?;
+
+        if base == 0.0 {
+            return Err(Error::ZeroAverage(from_window.from(), from_window.to()));
+        }
+
+        Ok((compared - base) / base * 100.0)
+    }
+
+    /// Gets average annual rainfall data for several countries, one request at a time. The batch stops early once
+    /// `cancelled` is set, returning the results that completed so far. Cancellation is checked between requests: a
+    /// request already in flight isn't interrupted, it completes and its result is returned. To bound the time a single
+    /// request takes, configure a timeout on the reqwest client passed to `with_http_client`.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_isos` - ISO3 country codes
+    /// `cancelled` - flag checked before each request, set it to stop the batch before the next request.
+    ///
+    /// # Returns
+    /// Pairs of country code and its result, in the order of `country_isos`, for every completed request.
+    pub fn get_average_annual_rainfall_many<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_isos: &[T],
+        cancelled: &AtomicBool,
+    ) -> Vec<(String, Result<f64, Error>)> {
+        let mut results = Vec::with_capacity(country_isos.len());
+
+        for country_iso in country_isos {
+            if cancelled.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let country_iso = country_iso.as_ref();
+            let result = self.get_average_annual_rainfall(from_year, to_year, country_iso);
+
+            results.push((String::from(country_iso), result));
+        }
+
+        results
+    }
+
+    /// Gets an average annual rainfall data for a historical year window (1920-1939 to 1980-1999). This is
+    /// `get_average_annual_rainfall_window` with a check that the window is historical; both kinds of window are
+    /// served by the same `annualavg` endpoint.
+    ///
+    /// # Arguments
+    /// `window` - a historical year interval.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM), or
+    /// `Error::DateRangeNotSupported` without making a request if `window` is a future window.
+    pub fn get_historical_average_annual_rainfall<T: AsRef<str>>(
+        &self,
+        window: YearWindow,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        self.get_average_annual_rainfall_of_kind(WindowKind::Historical, window, country_iso)
+    }
+
+    /// Gets an average annual rainfall data for a future, modeled year window (2020-2039 to 2080-2099). This is
+    /// `get_average_annual_rainfall_window` with a check that the window is future; the API serves future windows from
+    /// the same `annualavg` endpoint as historical ones, with one entry per GCM and emission scenario.
+    ///
+    /// # Arguments
+    /// `window` - a future year interval.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) and emission scenarios,
+    /// or `Error::DateRangeNotSupported` without making a request if `window` is a historical window.
+    pub fn get_future_average_annual_rainfall<T: AsRef<str>>(
+        &self,
+        window: YearWindow,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        self.get_average_annual_rainfall_of_kind(WindowKind::Future, window, country_iso)
+    }
+
+    /// Describes the client configuration in a single line, e.g. for logging at startup.
+    ///
+    /// # Returns
+    /// A human-readable summary of the configured domains, HTTP client, caching and certificate verification.
+    pub fn config_summary(&self) -> String {
+        format!(
+            "domain: {}, fallback domain: {}, HTTP client: {}, ETag cache: {}, Last-Modified cache: {}, \
+             certificate verification: {}",
+            self.domain_name,
+            self.fallback_domain_name.as_deref().unwrap_or("none"),
+            if self.custom_http_client {
+                "custom"
+            } else {
+                "default"
+            },
+            if self.etag_cache {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            if self.last_modified_cache {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            if self.accepts_invalid_certs {
+                "disabled"
+            } else {
+                "enabled"
+            },
+        )
+    }
+
+    /// Fetches the average annual rainfall response from the API and writes it as a Servirtium playback markdown file
+    /// that can be replayed offline in tests.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    /// `out_path` - path of the markdown file to write, an existing file is overwritten. The file is written to a
+    ///     temporary file next to it first and renamed, so concurrent captures never leave a partly written file.
+    pub fn capture_fixture<T: AsRef<str>, P: AsRef<Path>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+        out_path: P,
+    ) -> Result<(), Error> {
+        let path = self.construct_get_average_annual_value_path(
+            RAINFALL_VARIABLE,
+            from_year,
+            to_year,
+            country_iso,
+        );
+
+        let sent = self.send(&path, &HeaderMap::new())?;
+        let request_headers = collect_headers(&sent.request_headers);
+        let status = sent.response.status().as_u16();
+        let response_headers = collect_headers(sent.response.headers());
+        let response_body = sent.response.text()?;
+
+        let interaction = Interaction {
+            method: "GET",
+            path: &path,
+            request_headers: &request_headers,
+            status,
+            response_headers: &response_headers,
+            response_body: &response_body,
+        };
+
+        write_atomically(
+            out_path.as_ref(),
+            &markdown::write_interaction(0, &interaction),
+        )?;
+
+        Ok(())
+    }
+
+    /// Gets an average annual rainfall data rounded to the given number of decimal places. Ties are rounded half to
+    /// even (banker's rounding), e.g. 0.125 rounds to 0.12 and 0.135 to 0.14 at two decimals.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    /// `decimals` - number of decimal places to keep.
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM), rounded.
+    pub fn get_average_annual_rainfall_rounded<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+        decimals: u8,
+    ) -> Result<f64, Error> {
+        let average = self.get_average_annual_rainfall(from_year, to_year, country_iso)?;
+
+        Ok(round_half_to_even(average, decimals))
+    }
+
+    /// Checks locally, without a network call, whether the year interval is one of the 20-year windows supported by
+    /// the API. The API may still have no data for a supported window, which yields `Error::DateRangeNotSupported`.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval.
+    /// `to_year` - end of the year interval.
+    ///
+    /// # Returns
+    /// `true` if the year interval is a supported window.
+    pub fn is_supported_window(from_year: u16, to_year: u16) -> bool {
+        matches!(YearWindow::new(from_year), Ok(window) if window.to() == to_year)
+    }
+
+    /// Gets an average annual rainfall data for every historical year window. A window without data doesn't abort
+    /// the others, its entry holds the error instead.
+    ///
+    /// # Arguments
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Map from `(from_year, to_year)` to the result for that window, ordered chronologically.
+    pub fn get_historical_rainfall_map<T: AsRef<str>>(
+        &self,
+        country_iso: T,
+    ) -> BTreeMap<(u16, u16), Result<f64, Error>> {
+        YearWindow::historical()
+            .into_iter()
+            .map(|window| {
+                let result = self.get_average_annual_rainfall_window(window, country_iso.as_ref());
+
+                ((window.from(), window.to()), result)
+            })
+            .collect()
+    }
+
+    /// Lists the countries that can be queried. The API has no listing endpoint, so the list is bundled with the
+    /// crate; the `Result` leaves room for a live lookup.
+    ///
+    /// # Returns
+    /// Supported countries sorted by their ISO3 code.
+    pub fn get_supported_countries(&self) -> Result<Vec<Country>, Error> {
+        Ok(COUNTRIES
+            .iter()
+            .map(|(iso3, name)| Country {
+                iso3: String::from(*iso3),
+                name: String::from(*name),
+            })
+            .collect())
+    }
+
+    /// Gets the average annual rainfall values of all Global Circulation Models (GCM) reduced with a caller-supplied
+    /// function, e.g. a trimmed mean or a median.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    /// `aggregate` - reduces the per-GCM values, in the order returned by the API, to a single value.
+    ///
+    /// # Returns
+    /// Result of `aggregate`.
+    pub fn get_annual_rainfall_aggregated<T: AsRef<str>, F: Fn(&[f64]) -> f64>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+        aggregate: F,
+    ) -> Result<f64, Error> {
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+        let values: Vec<f64> = data.iter().map(|datum| datum.annual_data.double).collect();
+
+        Ok(aggregate(&values))
+    }
+
+    /// Gets an average annual rainfall data together with the year interval the response actually covers, which guards
+    /// against the server clamping or substituting the requested interval.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) together with the
+    /// `(from_year, to_year)` interval reported in the response, or the requested interval if the response doesn't
+    /// report a parseable one.
+    pub fn get_average_annual_rainfall_with_source_window<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<(f64, (u16, u16)), Error> {
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+
+        let source_window = data
+            .first()
+            .and_then(|datum| Some((datum.from_year.parse().ok()?, datum.to_year.parse().ok()?)))
+            .unwrap_or((from_year, to_year));
+
+        Ok((average(&data), source_window))
+    }
+
+    /// Gets the population standard deviation of the average annual rainfall values of all Global Circulation Models
+    /// (GCM), e.g. to plot error bars around the average.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Population standard deviation of the per-GCM values, `0.0` if only one GCM reported a value.
+    pub fn get_rainfall_stddev<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+
+        Ok(standard_deviation(&data))
+    }
+
+    /// Gets an approximate average annual rainfall for a 20-year interval the API doesn't support directly, e.g.
+    /// 1970-1989, by linearly interpolating between the averages of the nearest supported windows before and after it.
+    /// This is an approximation: the API never computed a value for the requested interval. A supported window is
+    /// fetched directly.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Interpolated average annual rainfall or `Error::DateRangeNotSupported` if the interval isn't 20 years long or
+    /// doesn't lie between two supported windows.
+    pub fn get_interpolated_annual_rainfall<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let windows: Vec<YearWindow> = YearWindow::historical()
+            .into_iter()
+            .chain(YearWindow::future())
+            .collect();
+        let lower = windows
+            .iter()
+            .rev()
+            .find(|window| window.from() <= from_year);
+        let upper = windows.iter().find(|window| window.from() >= from_year);
+
+        let (lower, upper) = match (lower, upper) {
+            (Some(lower), Some(upper)) if to_year == from_year.saturating_add(19) => (lower, upper),
+            _ => return Err(Error::DateRangeNotSupported(from_year, to_year)),
+        };
+
+        let country_iso = country_iso.as_ref();
+        let lower_average = self.get_average_annual_rainfall_window(*lower, country_iso)?;
+
+        if lower == upper {
+            return Ok(lower_average);
+        }
+
+        let upper_average = self.get_average_annual_rainfall_window(*upper, country_iso)?;
+        let weight = f64::from(from_year - lower.from()) / f64::from(upper.from() - lower.from());
+
+        Ok(lower_average + (upper_average - lower_average) * weight)
+    }
+
+    /// Gets an average annual rainfall data as a JSON object, e.g. to pass straight through a web handler. The object
+    /// has the fields `country`, `from_year`, `to_year`, `value` (the average) and `model_count` (number of Global
+    /// Circulation Models that reported a value).
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// The JSON object.
+    pub fn get_annual_rainfall_json<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<serde_json::Value, Error> {
+        let country_iso = country_iso.as_ref();
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+
+        Ok(json!({
+            "country": country_iso,
+            "from_year": from_year,
+            "to_year": to_year,
+            "value": average(&data),
+            "model_count": data.len(),
+        }))
+    }
+
+    /// Gets the average annual values of several climate variables with a single request, for endpoints that accept a
+    /// comma-separated list of variables and return the per-GCM data of all of them in one document.
+    ///
+    /// # Arguments
+    /// `variables` - names of the climate variables as used in the API URL, e.g. `pr` and `tas`.
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Map from each variable present in the response to the average of its values from all Global Circulation
+    /// Models (GCM).
+    pub fn get_combined_annual_averages<T: AsRef<str>>(
+        &self,
+        variables: &[&str],
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<BTreeMap<String, f64>, Error> {
+        let path = self.construct_get_average_annual_value_path(
+            &variables.join(","),
+            from_year,
+            to_year,
+            country_iso,
+        );
+
+        let (data, _): (CombinedClimateData, _) = self.fetch_xml(&path)?;
+
+        if data.results.is_empty() {
+            return Err(Error::DateRangeNotSupported(from_year, to_year));
+        }
+
+        Ok(data
+            .by_variable()
+            .into_iter()
+            .map(|(variable, data)| (variable, average(&data)))
+            .collect())
+    }
+
+    /// Checks locally, without a network call, that the request arguments are well-formed and reports all of the
+    /// problems at once, e.g. for form validation. Passing validation doesn't guarantee that the API has data for the
+    /// request.
+    ///
+    /// Validation is opt-in: the fetch methods don't call it and send the request as given, so they keep reporting
+    /// the errors the API responds with, e.g. `Error::NotRecognizedByClimateWeb` for an unknown country.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval.
+    /// `to_year` - end of the year interval.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// `Ok(())` if the arguments are valid, otherwise every problem found.
+    pub fn validate_request<T: AsRef<str>>(
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if YearWindow::new(from_year).is_err() {
+            errors.push(ValidationError::StartYearNotSupported(from_year));
+        }
+
+        if to_year != from_year.saturating_add(19) {
+            errors.push(ValidationError::SpanNotSupported(from_year, to_year));
+        }
+
+        let country_iso = country_iso.as_ref();
+        if country_iso.len() != 3 || !country_iso.chars().all(|c| c.is_ascii_alphabetic()) {
+            errors.push(ValidationError::MalformedCountryCode(String::from(
+                country_iso,
+            )));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Gets the mean of the average annual rainfall over all historical year windows, a single climatology figure for
+    /// a country. Windows without data are skipped, so the mean only covers the windows the API has data for and
+    /// weighs each of them equally.
+    ///
+    /// # Arguments
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Mean of the per-window averages or `Error::DateRangeNotSupported` for the whole historical range if no window
+    /// has data.
+    pub fn get_historical_mean_rainfall<T: AsRef<str>>(
+        &self,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let mut averages = Vec::new();
+
+        for (_, result) in self.get_historical_rainfall_map(country_iso) {
+            match result {
+                Ok(average) => averages.push(average),
+                Err(Error::DateRangeNotSupported(_, _)) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        if averages.is_empty() {
+            let windows = YearWindow::historical();
+
+            return Err(Error::DateRangeNotSupported(
+                windows[0].from(),
+                windows[windows.len() - 1].to(),
+            ));
+        }
+
+        Ok(mean(averages).unwrap_or_default())
+    }
+
+    /// Gives access to the reqwest client used by this client, configured by the builder, e.g. to send adjacent
+    /// requests with the same settings. The client can only be used, not reconfigured, through the shared reference.
+    ///
+    /// # Returns
+    /// The underlying reqwest client.
+    pub fn http_client(&self) -> &ReqwestClient {
+        &self.http
+    }
+
+    /// Gets an average annual rainfall data from WorldBank Climate Data API, giving up once the deadline passes. Unlike
+    /// a per-request timeout, the deadline bounds the total time spent including retries and the waits between them.
+    ///
+    /// # Arguments
+    /// `deadline` - point in time by which the result must be available.
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) or
+    /// `Error::DeadlineExceeded` if the deadline passed first.
+    pub fn get_average_annual_rainfall_by<T: AsRef<str>>(
+        &self,
+        deadline: Instant,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let client = ClimateApiClient {
+            deadline: Some(deadline),
+            ..self.clone()
+        };
+
+        client.get_average_annual_rainfall(from_year, to_year, country_iso)
+    }
+
+    /// Gets average annual rainfall data for arbitrary combinations of country and year interval. Up to four requests
+    /// are sent concurrently.
+    ///
+    /// # Arguments
+    /// `requests` - triples of ISO3 country code, start and end of the year interval.
+    ///
+    /// # Returns
+    /// Result for each request, in the order of `requests`.
+    pub fn get_average_annual_rainfall_batch(
+        &self,
+        requests: &[(String, u16, u16)],
+    ) -> Vec<Result<f64, Error>> {
+        self.batch(requests, |client, country_iso, from_year, to_year| {
+            client.get_average_annual_rainfall(from_year, to_year, country_iso)
+        })
+    }
+
+    /// Gets the monthly rainfall anomaly of a future year window, i.e. the change against the 1961-1999 control
+    /// period, averaged over all Global Circulation Models (GCM) and emission scenarios.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 2020 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average anomaly for each month from January to December, or `Error::DateRangeNotSupported` if the year
+    /// interval isn't a future window.
+    pub fn get_monthly_rainfall_anomaly<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<[f64; 12], Error> {
+        match YearWindow::new(from_year) {
+            Ok(window) if window.to() == to_year && window.kind() == WindowKind::Future => (),
+            _ => return Err(Error::DateRangeNotSupported(from_year, to_year)),
+        }
+
+        let path = self.construct_path(
+            MONTHLY_ANOMALY_TYPE,
+            RAINFALL_VARIABLE,
+            from_year,
+            to_year,
+            country_iso,
+        );
+
+        let (data, _): (MonthlyGcmData, _) = self.fetch_xml(&path)?;
+
+        let data = match data.results {
+            Some(data) => data,
+            None => return Err(Error::DateRangeNotSupported(from_year, to_year)),
+        };
+
+        let mut sums = [0.0; 12];
+        let mut counts = [0usize; 12];
+        for datum in &data {
+            for (month, value) in datum.month_vals.values.iter().take(12).enumerate() {
+                sums[month] += value;
+                counts[month] += 1;
+            }
+        }
+
+        let mut anomalies = [0.0; 12];
+        for ((anomaly, sum), count) in anomalies.iter_mut().zip(&sums).zip(&counts) {
+            if *count > 0 {
+                *anomaly = sum / *count as f64;
+            }
+        }
+
+        Ok(anomalies)
+    }
+
+    /// Gets the coefficient of variation (standard deviation divided by the average) of the average annual rainfall
+    /// values of all Global Circulation Models (GCM), a unit-free measure of how much the models disagree.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Coefficient of variation of the per-GCM values or `Error::ZeroAverage` if their average is zero.
+    pub fn get_rainfall_cv<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+        let average = average(&data);
+
+        if average == 0.0 {
+            return Err(Error::ZeroAverage(from_year, to_year));
+        }
+
+        Ok(standard_deviation(&data) / average)
+    }
+
+    /// Finds the country with the highest average annual rainfall among the given ones. Countries whose rainfall
+    /// can't be fetched are skipped. The requests are sent concurrently, as in `get_average_annual_rainfall_batch`.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_isos` - ISO3 country codes
+    ///
+    /// # Returns
+    /// ISO3 code and average annual rainfall of the wettest country, the error of the first country if none
+    /// succeeded or `Error::NoCountries` if `country_isos` is empty.
+    pub fn wettest_country<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_isos: &[T],
+    ) -> Result<(String, f64), Error> {
+        self.max_over_countries(
+            from_year,
+            to_year,
+            country_isos,
+            |client, country_iso, from_year, to_year| {
+                client.get_average_annual_rainfall(from_year, to_year, country_iso)
+            },
+        )
+    }
+
+    /// Gets a weighted average of the average annual rainfall values of all Global Circulation Models (GCM), e.g. to
+    /// weigh the models by skill scores.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    /// `weights` - weight of each GCM by its name as reported by the API, e.g. `bccr_bcm2_0`.
+    /// `skip_unmatched` - whether GCMs missing from `weights` are left out instead of weighted by 1.0.
+    ///
+    /// # Returns
+    /// Weighted average of the per-GCM values or `Error::NoDataForCountry` if the weights of the used GCMs sum to
+    /// zero.
+    pub fn get_weighted_average_annual_rainfall<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+        weights: &HashMap<String, f64>,
+        skip_unmatched: bool,
+    ) -> Result<f64, Error> {
+        let country_iso = country_iso.as_ref();
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+
+        let (weighted_sum, total_weight) = data
+            .iter()
+            .filter_map(|datum| match weights.get(&datum.gcm) {
+                Some(weight) => Some((datum.annual_data.double, *weight)),
+                None if skip_unmatched => None,
+                None => Some((datum.annual_data.double, 1.0)),
+            })
+            .fold(
+                (0.0, 0.0),
+                |(weighted_sum, total_weight), (value, weight)| {
+                    (weighted_sum + value * weight, total_weight + weight)
+                },
+            );
+
+        if total_weight == 0.0 {
+            return Err(Error::NoDataForCountry(String::from(country_iso)));
+        }
+
+        Ok(weighted_sum / total_weight)
+    }
+
+    /// Gets an average annual rainfall data together with its classification, e.g. for display.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) in millimeters and its
+    /// class, see `RainfallClass` for the thresholds.
+    pub fn get_rainfall_classified<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<(f64, RainfallClass), Error> {
+        let rainfall = self.get_average_annual_rainfall(from_year, to_year, country_iso)?;
+
+        Ok((rainfall, RainfallClass::from_mm(rainfall)))
+    }
+
+    /// Gets the average annual rainfall value of each Global Circulation Model (GCM) keyed by the model name, e.g. to
+    /// join with external model metadata. If the API reports a model more than once, the later values are keyed by
+    /// the model name suffixed with `#2`, `#3` and so on, in the order returned by the API.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Map from the GCM name to its average annual value.
+    pub fn get_annual_rainfall_model_map<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<BTreeMap<String, f64>, Error> {
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+
+        let mut occurrences: HashMap<&str, usize> = HashMap::new();
+        let mut model_map = BTreeMap::new();
+        for datum in &data {
+            let occurrence = occurrences.entry(&datum.gcm).or_insert(0);
+            *occurrence += 1;
+
+            let key = match *occurrence {
+                1 => datum.gcm.clone(),
+                occurrence => format!("{}#{}", datum.gcm, occurrence),
+            };
+            model_map.insert(key, datum.annual_data.double);
+        }
+
+        Ok(model_map)
+    }
+
+    /// Splits country codes into the ones found in the bundled country list and the rest, without a network call,
+    /// e.g. to report bad inputs before a batch. Codes are compared case-insensitively.
+    ///
+    /// # Arguments
+    /// `country_isos` - ISO3 country codes
+    ///
+    /// # Returns
+    /// Recognized and unrecognized codes, each in the order of `country_isos`.
+    pub fn partition_valid_countries<T: AsRef<str>>(
+        country_isos: &[T],
+    ) -> (Vec<String>, Vec<String>) {
+        country_isos
+            .iter()
+            .map(|country_iso| String::from(country_iso.as_ref()))
+            .partition(|country_iso| {
+                COUNTRIES
+                    .iter()
+                    .any(|(iso3, _)| iso3.eq_ignore_ascii_case(country_iso))
+            })
+    }
+
+    /// Counts the distinct emission scenarios in the response, e.g. to check that a future window covers all of the
+    /// expected scenarios. Historical windows have no scenarios.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Number of distinct scenarios reported by the Global Circulation Models (GCM).
+    pub fn get_scenario_count<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<usize, Error> {
+        let data = self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso)?;
+
+        let scenarios: HashSet<&str> = data
+            .iter()
+            .filter_map(|datum| datum.scenario.as_deref())
+            .collect();
+
+        Ok(scenarios.len())
+    }
+
+    /// Gets an average annual rainfall data from WorldBank Climate Data API, telling a country without model data
+    /// apart from a genuine zero average, e.g. for desert regions. Unlike `get_average_annual_rainfall`, an empty
+    /// response for a supported year interval isn't reported as an error.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) or `None` if the API has
+    /// no model data for the country.
+    pub fn get_average_annual_rainfall_if_available<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<Option<f64>, Error> {
+        let supported_window =
+            YearWindow::new(from_year).is_ok_and(|window| window.to() == to_year);
+
+        match self.get_annual_gcm_data(RAINFALL_VARIABLE, from_year, to_year, country_iso) {
+            Ok(data) => Ok(Some(average(&data))),
+            Err(Error::NoDataForCountry(_)) => Ok(None),
+            Err(Error::DateRangeNotSupported(..)) if supported_window => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Gets an average annual rainfall data from WorldBank Climate Data API, parsing the response as a stream of XML
+    /// events and keeping only a running sum and count rather than the per-GCM data. Suited to large responses, e.g.
+    /// many models or scenarios; for small ones `get_average_annual_rainfall` gives the same result.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM).
+    pub fn get_average_annual_rainfall_streaming<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let path = self.construct_get_average_annual_value_path(
+            RAINFALL_VARIABLE,
+            from_year,
+            to_year,
+            country_iso.as_ref(),
+        );
+
+        let FetchedResponse { url, body, .. } = self.fetch_annual(&path, from_year, to_year)?;
+
+        match streaming_average(&body) {
+            Ok(Some(average)) => Ok(average),
+            Ok(None) => Err(Error::DateRangeNotSupported(from_year, to_year)),
+            Err(StreamingError::NonFiniteValue(gcm)) => Err(Error::NonFiniteValue(gcm)),
+            Err(StreamingError::Xml(_))
+                if self.country_error_detection == CountryErrorDetection::Shape =>
+            {
+                Err(Error::NotRecognizedByClimateWeb)
+            }
+            Err(StreamingError::Xml(e)) => Err(Error::Deserialization(url, e)),
+        }
+    }
+
+    /// Gets a rolling mean of the average annual rainfall over `window_count` consecutive historical year windows,
+    /// a smoothed trend line. Windows without data are skipped, like in `get_historical_mean_rainfall`, so a rolling
+    /// mean covers the next windows the API has data for.
+    ///
+    /// # Arguments
+    /// `country_iso` - ISO3 country code
+    /// `window_count` - number of consecutive windows in each mean. It should be at least 1.
+    ///
+    /// # Returns
+    /// Map from the `(from_year, to_year)` range covered by each run of windows to its mean, ordered chronologically.
+    /// Empty if there are fewer windows with data than `window_count`. `Error::ZeroWindowCount` if `window_count` is
+    /// zero.
+    pub fn get_rolling_rainfall<T: AsRef<str>>(
+        &self,
+        country_iso: T,
+        window_count: usize,
+    ) -> Result<BTreeMap<(u16, u16), f64>, Error> {
+        if window_count == 0 {
+            return Err(Error::ZeroWindowCount);
+        }
+
+        let mut averages = Vec::new();
+
+        for (range, result) in self.get_historical_rainfall_map(country_iso) {
+            match result {
+                Ok(average) => averages.push((range, average)),
+                Err(Error::DateRangeNotSupported(_, _)) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(averages
+            .windows(window_count)
+            .map(|run| {
+                let from_year = (run[0].0).0;
+                let to_year = (run[run.len() - 1].0).1;
+                let mean = mean(run.iter().map(|(_, average)| *average)).unwrap_or_default();
+
+                ((from_year, to_year), mean)
+            })
+            .collect())
+    }
+
+    /// Gets an average annual rainfall data from WorldBank Climate Data API as a `Rainfall`, which carries the unit
+    /// and converts between millimeters and inches.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM).
+    pub fn get_average_annual_rainfall_typed<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<Rainfall, Error> {
+        let average = self.get_average_annual_rainfall(from_year, to_year, country_iso)?;
+
+        Ok(Rainfall::from_millimeters(average))
+    }
+
+    /// Gets an average annual rainfall data for the most recent historical year window, currently 1980-1999, so
+    /// callers don't have to hard-code it. If the API has no data for that window, the next earlier window is tried,
+    /// and so on down to 1920-1939.
+    ///
+    /// # Arguments
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// The window used and its average annual rainfall, or `Error::DateRangeNotSupported` for the whole historical
+    /// range if no window has data.
+    pub fn get_latest_annual_rainfall<T: AsRef<str>>(
+        &self,
+        country_iso: T,
+    ) -> Result<(YearWindow, f64), Error> {
+        let windows = YearWindow::historical();
+
+        for window in windows.iter().rev() {
+            match self.get_average_annual_rainfall_window(*window, country_iso.as_ref()) {
+                Ok(average) => return Ok((*window, average)),
+                Err(Error::DateRangeNotSupported(_, _)) | Err(Error::NoDataForCountry(_)) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(Error::DateRangeNotSupported(
+            windows[0].from(),
+            windows[windows.len() - 1].to(),
+        ))
+    }
+
+    /// Gets an average annual rainfall data for the default year window configured with
+    /// `ClimateApiClientBuilder::with_default_window`.
+    ///
+    /// # Arguments
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// Average of all of the average annual values from all Global Circulation Models (GCM) or
+    /// `Error::NoDefaultWindow` if the client has no default window.
+    pub fn get_average_annual_rainfall_default<T: AsRef<str>>(
+        &self,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        match self.default_window {
+            Some(window) => self.get_average_annual_rainfall_window(window, country_iso),
+            None => Err(Error::NoDefaultWindow),
+        }
+    }
+
+    /// Finds the country whose Global Circulation Models (GCM) disagree the most among the given ones, i.e. with the
+    /// highest coefficient of variation as computed by `get_rainfall_cv`. Countries whose data can't be fetched, or
+    /// whose average is zero, are skipped. The requests are sent concurrently, as in
+    /// `get_average_annual_rainfall_batch`.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_isos` - ISO3 country codes
+    ///
+    /// # Returns
+    /// ISO3 code and coefficient of variation of the most uncertain country, the error of the first country if none
+    /// succeeded or `Error::NoCountries` if `country_isos` is empty.
+    pub fn most_uncertain_country<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_isos: &[T],
+    ) -> Result<(String, f64), Error> {
+        self.max_over_countries(
+            from_year,
+            to_year,
+            country_isos,
+            |client, country_iso, from_year, to_year| {
+                client.get_rainfall_cv(from_year, to_year, country_iso)
+            },
+        )
+    }
+
+    /// Gets the average annual rainfall that the API computes across all Global Circulation Models (GCM) itself,
+    /// from the ensemble endpoint (`/climateweb/rest/v1/country/annualavg/ensemble/pr/...`). Where the API returns
+    /// one ensemble value per emission scenario, they are averaged.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// The ensemble average annual rainfall or `Error::DateRangeNotSupported` if the API has no ensemble data for the
+    /// year interval.
+    pub fn get_ensemble_average_annual_rainfall<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        let path = self.construct_path(
+            ANNUAL_AVERAGE_ENSEMBLE_TYPE,
+            RAINFALL_VARIABLE,
+            from_year,
+            to_year,
+            country_iso,
+        );
+
+        let (data, _): (AnnualEnsembleData, _) = self.fetch_xml(&path)?;
+
+        let results = data.results.unwrap_or_default();
+
+        mean(results.iter().map(|datum| datum.annual_data.double))
+            .ok_or(Error::DateRangeNotSupported(from_year, to_year))
+    }
+
+    /// Compares the client-side average of the per-GCM values with the ensemble average computed by the API, to check
+    /// that both agree.
+    ///
+    /// # Arguments
+    /// `from_year` - start of the year interval. It should be a value between 1920 and 2080 inclusive and it should be
+    ///     divisible by 20.
+    /// `to_year` - end of the year interval. It should be a value equal to `from_year` + 19.
+    /// `country_iso` - ISO3 country code
+    ///
+    /// # Returns
+    /// The client-side average, the ensemble average and the client-side average minus the ensemble average.
+    pub fn verify_average_against_ensemble<T: AsRef<str>>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<(f64, f64, f64), Error> {
+        let country_iso = country_iso.as_ref();
+        let client_average = self.get_average_annual_rainfall(from_year, to_year, country_iso)?;
+        let ensemble_average =
+            self.get_ensemble_average_annual_rainfall(from_year, to_year, country_iso)?;
+
+        Ok((
+            client_average,
+            ensemble_average,
+            client_average - ensemble_average,
+        ))
+    }
+
+    fn get_average_annual_rainfall_of_kind<T: AsRef<str>>(
+        &self,
+        kind: WindowKind,
+        window: YearWindow,
+        country_iso: T,
+    ) -> Result<f64, Error> {
+        if window.kind() != kind {
+            return Err(Error::DateRangeNotSupported(window.from(), window.to()));
+        }
+
+        self.get_average_annual_rainfall_window(window, country_iso)
+    }
+
+    /// Fetches a value for each of the countries concurrently with `batch` and picks the country with the highest
+    /// one. Countries whose value can't be fetched are skipped.
+    ///
+    /// # Returns
+    /// ISO3 code and value of the country with the highest value, the error of the first country if none succeeded
+    /// or `Error::NoCountries` if `country_isos` is empty.
+    fn max_over_countries<T, F>(
+        &self,
+        from_year: u16,
+        to_year: u16,
+        country_isos: &[T],
+        fetch: F,
+    ) -> Result<(String, f64), Error>
+    where
+        T: AsRef<str>,
+        F: Fn(&ClimateApiClient, &str, u16, u16) -> Result<f64, Error> + Sync,
+    {
+        let requests: Vec<(String, u16, u16)> = country_isos
+            .iter()
+            .map(|country_iso| (String::from(country_iso.as_ref()), from_year, to_year))
+            .collect();
+
+        let mut best: Option<(String, f64)> = None;
+        let mut first_error = None;
+
+        let results = self.batch(&requests, fetch);
+        for ((country_iso, _, _), result) in requests.into_iter().zip(results) {
+            match result {
+                Ok(value) if best.as_ref().is_none_or(|(_, max)| value > *max) => {
+                    best = Some((country_iso, value));
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match (best, first_error) {
+            (Some(best), _) => Ok(best),
+            (None, Some(e)) => Err(e),
+            (None, None) => Err(Error::NoCountries),
+        }
+    }
+
+    fn batch<R, F>(&self, requests: &[(String, u16, u16)], fetch: F) -> Vec<Result<R, Error>>
+    where
+        R: Send,
+        F: Fn(&ClimateApiClient, &str, u16, u16) -> Result<R, Error> + Sync,
+    {
+        let next = AtomicUsize::new(0);
+
+        let mut results: Vec<(usize, Result<R, Error>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..BATCH_CONCURRENCY.min(requests.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::new();
+
+                        loop {
+                            let index = next.fetch_add(1, Ordering::SeqCst);
+                            let (country_iso, from_year, to_year) = match requests.get(index) {
+                                Some(request) => request,
+                                None => return results,
+                            };
+
+                            let result = fetch(self, country_iso, *from_year, *to_year);
+                            results.push((index, result));
+                        }
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("Batch worker panicked"))
+                .collect()
+        });
+
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    fn get_annual_gcm_data<T: AsRef<str>>(
+        &self,
+        variable: &str,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<Vec<AnnualGcmDatum>, Error> {
+        let (data, _) =
+            self.get_annual_gcm_data_with_headers(variable, from_year, to_year, country_iso)?;
+
+        Ok(data)
+    }
+
+    fn get_annual_gcm_data_with_headers<T: AsRef<str>>(
+        &self,
+        variable: &str,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> Result<(Vec<AnnualGcmDatum>, Vec<(String, String)>), Error> {
+        let country_iso = country_iso.as_ref();
+        let path =
+            self.construct_get_average_annual_value_path(variable, from_year, to_year, country_iso);
+
+        let response = self.fetch_annual(&path, from_year, to_year)?;
+        let (data, headers): (AnnualGcmData, _) = self.parse_xml(response)?;
+
+        let data = extract_results(data, from_year, to_year, country_iso)?;
+
+        Ok((data, headers))
+    }
+
+    fn fetch_annual(
+        &self,
+        path: &str,
+        from_year: u16,
+        to_year: u16,
+    ) -> Result<FetchedResponse, Error> {
+        let unsupported_range = |body: &str, status: u16| match &self.unsupported_range {
+            Some(UnsupportedRange(predicate)) => predicate(body, status),
+            None => false,
+        };
+
+        let response = match self.fetch_checked(path) {
+            Err(Error::Reqwest(e))
+                if e.status()
+                    .is_some_and(|status| unsupported_range("", status.as_u16())) =>
+            {
+                return Err(Error::DateRangeNotSupported(from_year, to_year))
+            }
+            result => result?,
+        };
+
+        if unsupported_range(&response.body, response.status) {
+            return Err(Error::DateRangeNotSupported(from_year, to_year));
+        }
+
+        Ok(response)
+    }
+
+    fn fetch_xml<D: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<(D, Vec<(String, String)>), Error> {
+        let response = self.fetch_checked(path)?;
+
+        self.parse_xml(response)
+    }
+
+    fn parse_xml<D: DeserializeOwned>(
+        &self,
+        response: FetchedResponse,
+    ) -> Result<(D, Vec<(String, String)>), Error> {
+        let FetchedResponse {
+            url, headers, body, ..
+        } = response;
+
+        match quick_xml::de::from_str(&body) {
+            Ok(data) => Ok((data, headers)),
+            Err(_) if self.country_error_detection == CountryErrorDetection::Shape => {
+                Err(Error::NotRecognizedByClimateWeb)
+            }
+            Err(e) => Err(Error::Deserialization(url, e)),
+        }
+    }
+
+    fn fetch_checked(&self, path: &str) -> Result<FetchedResponse, Error> {
+        let detection = self.country_error_detection;
+
+        let response = match self.fetch(path) {
+            Err(Error::Reqwest(e))
+                if detection == CountryErrorDetection::Status
+                    && e.status().is_some_and(|status| status.is_client_error()) =>
+            {
+                return Err(Error::NotRecognizedByClimateWeb)
+            }
+            result => result?,
+        };
+
+        if detection == CountryErrorDetection::BodyText
+            && response.body.starts_with("Invalid country code")
+        {
+            return Err(Error::NotRecognizedByClimateWeb);
+        }
+
+        Ok(response)
+    }
+
+    fn fetch(&self, path: &str) -> Result<FetchedResponse, Error> {
+        let cache_key = format!("{}{}", self.domain_name, path);
+        if let Some(body) = self
+            .disk_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&cache_key))
+        {
+            return Ok(FetchedResponse {
+                url: cache_key,
+                status: StatusCode::OK.as_u16(),
+                headers: Vec::new(),
+                body,
+            });
+        }
+
+        let cached = match &self.response_cache {
+            Some(response_cache) => response_cache.lock().unwrap().get(&cache_key).cloned(),
+            None => None,
+        };
+
+        let mut request_headers = HeaderMap::new();
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request_headers.insert(IF_NONE_MATCH, etag.clone());
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request_headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+            }
+        }
+
+        let SentRequest {
+            domain_name,
+            response,
+            ..
+        } = self.send(path, &request_headers)?;
+
+        let url = String::from(response.url().as_str());
+        let status = response.status().as_u16();
+
+        if response.status() == StatusCode::NOT_MODIFIED && domain_name == self.domain_name {
+            if let Some(cached) = cached {
+                return Ok(FetchedResponse {
+                    url,
+                    status: StatusCode::OK.as_u16(),
+                    headers: cached.headers,
+                    body: cached.body,
+                });
+            }
+        }
+
+        let etag = if self.etag_cache {
+            response.headers().get(ETAG).cloned()
+        } else {
+            None
+        };
+        let last_modified = if self.last_modified_cache {
+            response.headers().get(LAST_MODIFIED).cloned()
+        } else {
+            None
+        };
+        let headers = collect_headers(response.headers());
+        let body = response.text()?;
+        let answered_key = format!("{}{}", domain_name, path);
+
+        if let Some(response_cache) = &self.response_cache {
+            if etag.is_some() || last_modified.is_some() {
+                response_cache.lock().unwrap().insert(
+                    answered_key.clone(),
+                    CachedResponse {
+                        etag,
+                        last_modified,
+                        headers: headers.clone(),
+                        body: body.clone(),
+                    },
+                );
+            }
+        }
+
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.put(&answered_key, &body)?;
+        }
+
+        Ok(FetchedResponse {
+            url,
+            status,
+            headers,
+            body,
+        })
+    }
+
+    fn send(&self, path: &str, headers: &HeaderMap) -> Result<SentRequest<'_>, Error> {
+        let mut retries = 0;
+
+        loop {
+            match self.send_with_fallback(path, headers) {
+                Err(e) if retries < self.max_retries && is_retryable(&e) && self.take_retry() => {
+                    let delay = self.retry_delay(retries);
+
+                    let remaining = self.remaining_until_deadline();
+                    if remaining.is_some_and(|remaining| remaining <= delay) {
+                        return Err(Error::DeadlineExceeded);
+                    }
+
+                    thread::sleep(delay);
+                    retries += 1;
+                }
+                Err(Error::Timeout(_))
+                    if self.remaining_until_deadline() == Some(Duration::from_secs(0)) =>
+                {
+                    return Err(Error::DeadlineExceeded)
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn retry_delay(&self, retries: u32) -> Duration {
+        let delay = RETRY_BASE_DELAY * 2u32.saturating_pow(retries);
+
+        match &self.retry_jitter {
+            Some(jitter) => jitter.apply(delay),
+            None => delay,
+        }
+    }
+
+    fn remaining_until_deadline(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    fn take_retry(&self) -> bool {
+        match &self.retry_budget {
+            Some(retry_budget) => retry_budget.try_acquire(),
+            None => true,
+        }
+    }
+
+    /// Sends the request to the primary domain and, on a transport error, to the fallback domain. Conditional
+    /// request headers carry validators cached for the primary domain, so they aren't sent to the fallback.
+    ///
+    /// # Returns
+    /// The domain name that answered, the request headers sent to it and its response.
+    fn send_with_fallback(
+        &self,
+        path: &str,
+        headers: &HeaderMap,
+    ) -> Result<SentRequest<'_>, Error> {
+        let result = self.send_to_domain(&self.domain_name, path, headers);
+
+        match (result, &self.fallback_domain_name) {
+            (Err(e), Some(fallback_domain_name)) if is_transport_error(&e) => {
+                let mut headers = headers.clone();
+                headers.remove(IF_NONE_MATCH);
+                headers.remove(IF_MODIFIED_SINCE);
+
+                self.send_to_domain(fallback_domain_name, path, &headers)
+            }
+            (result, _) => result,
+        }
+    }
+
+    fn send_to_domain<'a>(
+        &self,
+        domain_name: &'a str,
+        path: &str,
+        headers: &HeaderMap,
+    ) -> Result<SentRequest<'a>, Error> {
+        let url = format!("{}{}", domain_name, path);
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if !rate_limiter.acquire_before(self.deadline) {
+                return Err(Error::DeadlineExceeded);
+            }
+        }
+
+        let mut request = self.http.get(&url).headers(headers.clone());
+        if let Some(HeaderProvider(provider)) = &self.header_provider {
+            for (name, value) in provider() {
+                request = request.header(name.as_str(), value.as_str());
+            }
+        }
+
+        if let Some(remaining) = self.remaining_until_deadline() {
+            if remaining == Duration::from_secs(0) {
+                return Err(Error::DeadlineExceeded);
+            }
+
+            request = request.timeout(remaining);
+        }
+
+        let request = request.build()?;
+        let request_headers = self.sent_headers(&request);
+
+        self.notify_request_hook(&url, None, None);
+
+        let start = Instant::now();
+        let result = self.http.execute(request);
+        let status = result
+            .as_ref()
+            .ok()
+            .map(|response| response.status().as_u16());
+
+        self.notify_request_hook(&url, status, Some(start.elapsed()));
+
+        Ok(SentRequest {
+            domain_name,
+            request_headers,
+            response: result?.error_for_status()?,
+        })
+    }
+
+    /// Headers that go out with the request: the client's default headers overridden by the request's own, plus the
+    /// `accept` and `host` headers that reqwest adds when they're missing.
+    fn sent_headers(&self, request: &ReqwestRequest) -> HeaderMap {
+        let mut headers = self.default_headers.clone();
+        for (name, value) in request.headers() {
+            headers.insert(name, value.clone());
+        }
+
+        headers
+            .entry(ACCEPT)
+            .or_insert_with(|| HeaderValue::from_static("*/*"));
+        if let Ok(host) = HeaderValue::from_str(host_of(request.url().as_str())) {
+            headers.entry(HOST).or_insert(host);
+        }
+
+        headers
+    }
+
+    fn notify_request_hook(&self, url: &str, status: Option<u16>, elapsed: Option<Duration>) {
+        if let Some(RequestHook(hook)) = &self.request_hook {
+            hook(&RequestInfo {
+                url: String::from(url),
+                status,
+                elapsed,
+            });
+        }
+    }
+
+    fn construct_get_average_annual_value_path<T: AsRef<str>>(
+        &self,
+        variable: &str,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> String {
+        self.construct_path(
+            ANNUAL_AVERAGE_TYPE,
+            variable,
+            from_year,
+            to_year,
+            country_iso,
+        )
+    }
+
+    fn construct_path<T: AsRef<str>>(
+        &self,
+        data_type: &str,
+        variable: &str,
+        from_year: u16,
+        to_year: u16,
+        country_iso: T,
+    ) -> String {
+        format!(
+            "/climateweb/rest/v1/country/{}/{}/{}/{}/{}.xml",
+            data_type,
+            variable,
+            from_year,
+            to_year,
+            country_iso.as_ref()
+        )
+    }
+}
+
+fn extract_results(
+    data: AnnualGcmData,
+    from_year: u16,
+    to_year: u16,
+    country_iso: &str,
+) -> Result<Vec<AnnualGcmDatum>, Error> {
+    match data.results {
+        Some(results) if results.is_empty() => {
+            Err(Error::NoDataForCountry(String::from(country_iso)))
+        }
+        Some(results) => match results
+            .iter()
+            .find(|datum| !datum.annual_data.double.is_finite())
+        {
+            Some(datum) => Err(Error::NonFiniteValue(datum.gcm.clone())),
+            None => Ok(results),
+        },
+        None => Err(Error::DateRangeNotSupported(from_year, to_year)),
+    }
+}
+
+/// Arithmetic mean of the per-GCM values, `0.0` if there are none.
+fn average(data: &[AnnualGcmDatum]) -> f64 {
+    mean(data.iter().map(|datum| datum.annual_data.double)).unwrap_or(0.0)
+}
+
+/// Arithmetic mean of `values`, `None` if there are none. The values are summed left to right as `f64`, which is how
+/// the averages in the recorded fixtures are computed, so a compensated sum would change their last digit. The count
+/// is a `usize`, so it can't overflow before memory runs out, and it's converted to `f64` only for the division. The
+/// conversion is exact for up to 2^53 values.
+fn mean<I: IntoIterator<Item = f64>>(values: I) -> Option<f64> {
+    let (sum, count) = values
+        .into_iter()
+        .fold((0.0_f64, 0_usize), |(sum, count), value| {
+            (sum + value, count + 1)
+        });
+
+    match count {
+        0 => None,
+        _ => Some(sum / count as f64),
+    }
+}
+
+#[derive(Debug)]
+enum StreamingError {
+    NonFiniteValue(String),
+    Xml(DeError),
+}
+
+impl From<quick_xml::Error> for StreamingError {
+    fn from(e: quick_xml::Error) -> Self {
+        StreamingError::Xml(DeError::from(e))
+    }
+}
+
+impl From<ParseFloatError> for StreamingError {
+    fn from(e: ParseFloatError) -> Self {
+        StreamingError::Xml(DeError::from(e))
+    }
+}
+
+/// Same as `average` over the parsed annual average response, but reading the XML as a stream of events and keeping
+/// only the running sum and count. `None` if the response has no per-GCM data.
+fn streaming_average(body: &str) -> Result<Option<f64>, StreamingError> {
+    let mut reader = Reader::from_str(body);
+    reader.trim_text(true);
+
+    let mut depth = 0;
+    let mut element = Vec::new();
+    let mut in_annual_data = false;
+    let mut gcm = String::new();
+    let mut sum = 0.0_f64;
+    let mut count: usize = 0;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                element = e.name().as_ref().to_vec();
+                if depth == 0 && element != b"list" {
+                    return Err(DeError::UnexpectedStart(element).into());
+                }
+                if element == b"annualData" {
+                    in_annual_data = true;
+                }
+                depth += 1;
+            }
+            Event::Empty(e) if depth == 0 && e.name().as_ref() != b"list" => {
+                return Err(DeError::UnexpectedStart(e.name().as_ref().to_vec()).into());
+            }
+            Event::Text(e) => {
+                let text = e.unescape()?;
+
+                if element == b"gcm" {
+                    gcm = text.into_owned();
+                } else if in_annual_data && ANNUAL_VALUE_ELEMENTS.contains(&element.as_slice()) {
+                    let value: f64 = text.trim().parse()?;
+                    if !value.is_finite() {
+                        return Err(StreamingError::NonFiniteValue(gcm));
+                    }
+
+                    sum += value;
+                    count += 1;
+                }
+            }
+            Event::End(e) => {
+                if e.name().as_ref() == b"annualData" {
+                    in_annual_data = false;
+                }
+                element.clear();
+                depth -= 1;
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    match count {
+        0 => Ok(None),
+        _ => Ok(Some(sum / count as f64)),
+    }
+}
+
+fn standard_deviation(data: &[AnnualGcmDatum]) -> f64 {
+    let average = average(data);
+    let variance = mean(data.iter().map(|datum| {
+        let deviation = datum.annual_data.double - average;
+
+        deviation * deviation
+    }));
+
+    variance.map(f64::sqrt).unwrap_or(0.0)
+}
+
+fn round_half_to_even(value: f64, decimals: u8) -> f64 {
+    let scale = 10f64.powi(i32::from(decimals));
+    let scaled = value * scale;
+
+    let rounded = if (scaled - scaled.trunc()).abs() == 0.5 {
+        2.0 * (scaled / 2.0).round()
+    } else {
+        scaled.round()
+    };
+
+    rounded / scale
+}
+
+fn collect_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                String::from(name.as_str()),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
+
+fn normalize_domain_name(domain_name: &str) -> String {
+    String::from(domain_name.trim_end_matches('/'))
+}
+
+fn host_of(domain_name: &str) -> &str {
+    let without_scheme = match domain_name.find("://") {
+        Some(index) => &domain_name[index + 3..],
+        None => domain_name,
+    };
+
+    without_scheme.split('/').next().unwrap_or(without_scheme)
+}
+
+fn is_retryable(e: &Error) -> bool {
+    match e {
+        Error::Reqwest(e) if e.status().is_some_and(|status| status.is_server_error()) => true,
+        e => is_transport_error(e),
+    }
+}
+
+fn is_transport_error(e: &Error) -> bool {
+    match e {
+        Error::Timeout(_) | Error::Connect(_) | Error::Tls(_) => true,
+        Error::Reqwest(e) => e.is_request(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        average, extract_results, mean, round_half_to_even, standard_deviation, streaming_average,
+        RETRY_BASE_DELAY,
+    };
+    use crate::data::annual_gcm_data::{AnnualData, AnnualGcmData, AnnualGcmDatum};
+    use crate::markdown::read_response_body;
+    use crate::request_hook::RequestInfo;
+    use crate::stub_server::{annual_gcm_xml, request_path, response, xml_response, StubServer};
+    use crate::temp_dir::TempDir;
+    use crate::{
+        error::Error, error::ValidationError, ClimateApiClient, ClimateApiClientBuilder,
+        CountryErrorDetection, RainfallClass, YearWindow,
+    };
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::redirect::Policy;
+    use servirtium::{
+        servirtium_playback_test, servirtium_record_test, ServirtiumConfiguration, ServirtiumMode,
+        ServirtiumServer,
+    };
+    use std::collections::HashMap;
+    use std::io::Write;
+    use std::net::{SocketAddr, TcpListener};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use std::{fs, thread};
+
+    fn servirtium_configure(config: &mut ServirtiumConfiguration) {
+        config.set_domain_name("https://servirtium.github.io/worldbank-climate-recordings");
+        config.set_fail_if_markdown_changed(true);
+
+        config.add_record_response_mutations(|builder| {
+            builder.remove_headers(vec!["set-cookie", "date"])
+        });
+
+        config.add_playback_response_mutations(|builder| {
+            builder.add_header("date", "Sun, 02 Aug 2020 09:53:31 GMT")
+        });
+    }
+
+    /// Runs the response body of a playback fixture through the current parse and average pipeline and asserts the
+    /// computed average, so parser changes are caught against committed recordings.
+    fn assert_fixture_average(
+        path: &str,
+        from_year: u16,
+        to_year: u16,
+        country_iso: &str,
+        expected: f64,
+    ) {
+        let markdown = fs::read_to_string(path).unwrap();
+        let body = read_response_body(&markdown).unwrap();
+        let data: AnnualGcmData = quick_xml::de::from_str(&body).unwrap();
+        let actual = average(&extract_results(data, from_year, to_year, country_iso).unwrap());
+
+        assert!(
+            (actual - expected).abs() < f64::EPSILON,
+            "{}: expected average {}, got {}",
+            path,
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists_direct() {
+        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists_playback() {
+        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists_record() {
+        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists(
+        climate_api: ClimateApiClient,
+    ) {
+        assert!(
+            (climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap()
+                - 988.8454972331015)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_average_rainfall_for_france_from_1980_to_1999_exists_direct() {
+        test_average_rainfall_for_france_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_France_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_france_from_1980_to_1999_exists_playback() {
+        test_average_rainfall_for_france_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_France_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_france_from_1980_to_1999_exists_record() {
+        test_average_rainfall_for_france_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    fn test_average_rainfall_for_france_from_1980_to_1999_exists(climate_api: ClimateApiClient) {
+        assert!(
+            (climate_api
+                .get_average_annual_rainfall(1980, 1999, "fra")
+                .unwrap()
+                - 913.7986955122727)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists_direct() {
+        test_average_rainfall_for_egypt_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Egypt_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists_playback() {
+        test_average_rainfall_for_egypt_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Egypt_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists_record() {
+        test_average_rainfall_for_egypt_from_1980_to_1999_exists(ClimateApiClient::new());
+    }
+
+    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists(climate_api: ClimateApiClient) {
+        assert!(
+            (climate_api
+                .get_average_annual_rainfall(1980, 1999, "egy")
+                .unwrap()
+                - 54.58587712129825)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist_direct() {
+        test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1985_to_1995_Does_Not_Exist.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist_playback() {
+        test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1985_to_1995_Does_Not_Exist.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist_record() {
+        test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
+        climate_api: ClimateApiClient,
+    ) {
+        let result = climate_api.get_average_annual_rainfall(1985, 1995, "gbr");
+
+        match result {
+            Err(err) => match err {
+                Error::DateRangeNotSupported(1985, 1995) => (),
+                _ => panic!("The function returned a wrong error: {}", err),
+            },
+            _ => panic!("The function call should return an error"),
+        }
+    }
+
+    #[test]
+    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist_direct() {
+        test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Middle_Earth_From_1980_to_1999_Does_Not_Exist.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist_playback() {
+        test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Middle_Earth_From_1980_to_1999_Does_Not_Exist.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist_record() {
+        test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
+        climate_api: ClimateApiClient,
+    ) {
+        let result = climate_api.get_average_annual_rainfall(1980, 1999, "mde");
+
+        match result {
+            Err(err) => match err {
+                Error::NotRecognizedByClimateWeb => (),
+                _ => panic!("The function returned a wrong error: {}", err),
+            },
+            _ => panic!("The function call should return an error"),
+        }
+    }
+
+    #[test]
+    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist_direct() {
+        test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_And_France_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist_playback() {
+        test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_And_France_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist_record() {
+        test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
+            ClimateApiClient::new(),
+        );
+    }
+
+    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
+        climate_api: ClimateApiClient,
+    ) {
+        let (gbr, fra) = climate_api
+            .get_average_annual_rainfall_for_two(1980, 1999, "gbr", "fra")
+            .unwrap();
+
+        assert!((gbr - 988.8454972331015).abs() < f64::EPSILON);
+        assert!((fra - 913.7986955122727).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_ping_succeeds_direct() {
+        test_ping_succeeds(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_ping_succeeds_playback() {
+        test_ping_succeeds(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_ping_succeeds_record() {
+        test_ping_succeeds(ClimateApiClient::new());
+    }
+
+    fn test_ping_succeeds(climate_api: ClimateApiClient) {
+        climate_api.ping().unwrap();
+    }
+
+    #[test]
+    fn test_ping_fails_for_unreachable_domain() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name("http://127.0.0.1:1")
+            .build();
+
+        assert!(climate_api.ping().is_err());
+    }
+
+    #[test]
+    fn test_average_rainfall_from_fallback_domain() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0, 20.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name("http://127.0.0.1:1")
+            .with_fallback_domain(server.url())
+            .build();
+
+        let rainfall = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((rainfall - 15.0).abs() < f64::EPSILON);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_timed_average_rainfall_measures_the_request() {
+        let delay = Duration::from_millis(200);
+        let server = StubServer::start(move |_| {
+            thread::sleep(delay);
+            xml_response(&annual_gcm_xml(&[10.0, 20.0]))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let (average, elapsed) = climate_api
+            .get_average_annual_rainfall_timed(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((average - 15.0).abs() < f64::EPSILON);
+        assert!(elapsed >= delay);
+    }
+
+    #[test]
+    fn test_average_annual_value_of_rainfall_for_great_britain_from_1980_to_1999_direct() {
+        test_average_annual_value_of_rainfall_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_annual_value_of_rainfall_for_great_britain_from_1980_to_1999_playback() {
+        test_average_annual_value_of_rainfall_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_annual_value_of_rainfall_for_great_britain_from_1980_to_1999_record() {
+        test_average_annual_value_of_rainfall_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    fn test_average_annual_value_of_rainfall_for_great_britain_from_1980_to_1999(
+        climate_api: ClimateApiClient,
+    ) {
+        assert!(
+            (climate_api
+                .get_average_annual_value("pr", 1980, 1999, "gbr")
+                .unwrap()
+                - 988.8454972331015)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_average_rainfall_follows_redirect() {
+        let server = StubServer::start(|request| {
+            let path = request_path(request);
+
+            if path.starts_with("/moved") {
+                xml_response(&annual_gcm_xml(&[10.0, 20.0]))
+            } else {
+                let location = format!("/moved{}", path);
+
+                response("302 Found", &[("location", location.as_str())], "")
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_redirect_policy(Policy::limited(1))
+            .build();
+
+        assert!(
+            (climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap()
+                - 15.0)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_rainfall_change_pct_between_1920_and_1980() {
+        let server = StubServer::start(|request| {
+            if request_path(request).contains("/1920/1939/") {
+                xml_response(&annual_gcm_xml(&[400.0, 600.0]))
+            } else {
+                xml_response(&annual_gcm_xml(&[500.0, 700.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let change = climate_api
+            .get_rainfall_change_pct(
+                "gbr",
+                YearWindow::new(1920).unwrap(),
+                YearWindow::new(1980).unwrap(),
+            )
+            .unwrap();
+
+        assert!((change - 20.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_average_rainfall_many_finishes_in_flight_request_when_cancelled() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let served = AtomicUsize::new(0);
+        let server_cancelled = Arc::clone(&cancelled);
+        let server = StubServer::start(move |_| {
+            if served.fetch_add(1, Ordering::SeqCst) + 1 == 2 {
+                server_cancelled.store(true, Ordering::SeqCst);
+            }
+
+            xml_response(&annual_gcm_xml(&[10.0]))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let results = climate_api.get_average_annual_rainfall_many(
+            1980,
+            1999,
+            &["gbr", "fra", "egy", "deu"],
+            &cancelled,
+        );
+
+        assert_eq!(server.requests().len(), 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "gbr");
+        assert_eq!(results[1].0, "fra");
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+    }
+
+    #[test]
+    fn test_historical_and_future_windows_use_annual_average_endpoint() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        climate_api
+            .get_historical_average_annual_rainfall(YearWindow::new(1980).unwrap(), "gbr")
+            .unwrap();
+        climate_api
+            .get_future_average_annual_rainfall(YearWindow::new(2020).unwrap(), "gbr")
+            .unwrap();
+
+        let paths: Vec<String> = server
+            .requests()
+            .iter()
+            .map(|request| String::from(request_path(request)))
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "/climateweb/rest/v1/country/annualavg/pr/1980/1999/gbr.xml",
+                "/climateweb/rest/v1/country/annualavg/pr/2020/2039/gbr.xml",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_future_average_rainfall_rejects_historical_window() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name("http://127.0.0.1:1")
+            .build();
+
+        match climate_api.get_future_average_annual_rainfall(YearWindow::new(1980).unwrap(), "gbr")
+        {
+            Err(Error::DateRangeNotSupported(1980, 1999)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_http2_prior_knowledge_sends_http2_preface() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_http2_prior_knowledge(true)
+            .build();
+
+        // The stub server only speaks HTTP/1.1, so the request itself fails after the preface is sent.
+        let _ = climate_api.ping();
+
+        assert!(server.requests()[0].starts_with("PRI * HTTP/2.0"));
+    }
+
+    #[test]
+    fn test_average_rainfall_with_headers_response_for_great_britain_from_1980_to_1999_direct() {
+        test_average_rainfall_with_headers_response_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_with_headers_response_for_great_britain_from_1980_to_1999_playback() {
+        test_average_rainfall_with_headers_response_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_with_headers_response_for_great_britain_from_1980_to_1999_record() {
+        test_average_rainfall_with_headers_response_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    fn test_average_rainfall_with_headers_response_for_great_britain_from_1980_to_1999(
+        climate_api: ClimateApiClient,
+    ) {
+        let (average, headers) = climate_api
+            .get_average_annual_rainfall_with_headers_response(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((average - 988.8454972331015).abs() < f64::EPSILON);
+        assert!(headers
+            .iter()
+            .any(|(name, value)| name == "content-type" && value.starts_with("application/xml")));
+    }
+
+    #[test]
+    fn test_etag_cache_serves_cached_average_on_not_modified() {
+        let served = AtomicUsize::new(0);
+        let server = StubServer::start(move |_| match served.fetch_add(1, Ordering::SeqCst) {
+            0 => response(
+                "200 OK",
+                &[("content-type", "application/xml"), ("etag", "\"v1\"")],
+                &annual_gcm_xml(&[10.0, 20.0]),
+            ),
+            _ => response("304 Not Modified", &[("etag", "\"v1\"")], ""),
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_etag_cache(true)
+            .build();
+
+        let first = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+        let second = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((first - 15.0).abs() < f64::EPSILON);
+        assert!((second - 15.0).abs() < f64::EPSILON);
+        assert!(server.requests()[1]
+            .to_lowercase()
+            .contains("if-none-match: \"v1\""));
+    }
+
+    #[test]
+    fn test_config_summary_contains_domain() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name("http://localhost:61417")
+            .build();
+
+        assert!(climate_api
+            .config_summary()
+            .contains("domain: http://localhost:61417"));
+    }
+
+    #[test]
+    fn test_slow_response_maps_to_timeout_error() {
+        let server = StubServer::start(|_| {
+            thread::sleep(Duration::from_secs(2));
+
+            xml_response(&annual_gcm_xml(&[10.0]))
+        });
+        let http_client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_millis(100))
+            .build()
+            .unwrap();
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_http_client(http_client)
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1980, 1999, "gbr") {
+            Err(Error::Timeout(_)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_refused_connection_maps_to_connect_error() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name("http://127.0.0.1:1")
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1980, 1999, "gbr") {
+            Err(Error::Connect(_)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_capture_fixture_writes_replayable_markdown() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0, 20.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+        let temp_dir = TempDir::new("worldbank_climate_capture_fixture_test");
+        let out_path = temp_dir.path().join("capture.md");
+
+        climate_api
+            .capture_fixture(1980, 1999, "gbr", &out_path)
+            .unwrap();
+
+        let markdown = fs::read_to_string(&out_path).unwrap();
+        assert!(markdown.starts_with(
+            "## Interaction 0: GET /climateweb/rest/v1/country/annualavg/pr/1980/1999/gbr.xml"
+        ));
+        for header in server.requests()[0].lines().skip(1) {
+            if !header.is_empty() {
+                assert!(markdown.contains(&header.to_lowercase()), "{}", header);
+            }
+        }
+
+        let _guard = ServirtiumServer::prepare_for_test(
+            ServirtiumMode::Playback,
+            &out_path,
+            Some(servirtium_configure),
+        );
+        let rainfall = ClimateApiClient::for_servirtium()
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((rainfall - 15.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_rounded_average_rainfall_for_great_britain_from_1980_to_1999_direct() {
+        test_rounded_average_rainfall_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rounded_average_rainfall_for_great_britain_from_1980_to_1999_playback() {
+        test_rounded_average_rainfall_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rounded_average_rainfall_for_great_britain_from_1980_to_1999_record() {
+        test_rounded_average_rainfall_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    fn test_rounded_average_rainfall_for_great_britain_from_1980_to_1999(
+        climate_api: ClimateApiClient,
+    ) {
+        assert!(
+            (climate_api
+                .get_average_annual_rainfall_rounded(1980, 1999, "gbr", 2)
+                .unwrap()
+                - 988.85)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_round_half_to_even() {
+        assert!((round_half_to_even(2.5, 0) - 2.0).abs() < f64::EPSILON);
+        assert!((round_half_to_even(3.5, 0) - 4.0).abs() < f64::EPSILON);
+        assert!((round_half_to_even(0.125, 2) - 0.12).abs() < f64::EPSILON);
+        assert!((round_half_to_even(-2.5, 0) + 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_is_supported_window() {
+        assert!(ClimateApiClient::is_supported_window(1980, 1999));
+        assert!(!ClimateApiClient::is_supported_window(1980, 1998));
+        assert!(!ClimateApiClient::is_supported_window(1985, 2004));
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_from_local_servirtium_playback() {
+        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(
+            ClimateApiClient::for_servirtium(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_average_rainfall_for_great_britain_from_local_servirtium_record() {
+        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(
+            ClimateApiClient::for_servirtium(),
+        );
+    }
+
+    #[test]
+    fn test_request_hook_observes_request() {
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let hook_observed = Arc::clone(&observed);
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0, 20.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_request_hook(Arc::new(move |info: &RequestInfo| {
+                hook_observed.lock().unwrap().push(info.clone())
+            }))
+            .build();
+
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        let observed = observed.lock().unwrap();
+        let url = format!(
+            "{}/climateweb/rest/v1/country/annualavg/pr/1980/1999/gbr.xml",
+            server.url()
+        );
+
+        assert_eq!(observed.len(), 2);
+        assert_eq!(observed[0].url, url);
+        assert_eq!(observed[0].status, None);
+        assert_eq!(observed[1].url, url);
+        assert_eq!(observed[1].status, Some(200));
+        assert!(observed[1].elapsed.is_some());
+    }
+
+    #[test]
+    fn test_average_rainfall_with_integer_value() {
+        let server = StubServer::start(|_| {
+            xml_response(
+                "<list><domain.web.AnnualGcmDatum><gcm>gcm_0</gcm><variable>pr</variable>\
+                 <fromYear>1980</fromYear><toYear>1999</toYear>\
+                 <annualData><double>1000</double></annualData></domain.web.AnnualGcmDatum></list>",
+            )
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        assert!(
+            (climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap()
+                - 1000.0)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_average_rainfall_with_alternate_value_element_name() {
+        let server = StubServer::start(|_| {
+            xml_response(
+                "<list><domain.web.AnnualGcmDatum><gcm>gcm_0</gcm><variable>pr</variable>\
+                 <fromYear>1980</fromYear><toYear>1999</toYear>\
+                 <annualData><float>1000.5</float></annualData></domain.web.AnnualGcmDatum>\
+                 <domain.web.AnnualGcmDatum><gcm>gcm_1</gcm><variable>pr</variable>\
+                 <fromYear>1980</fromYear><toYear>1999</toYear>\
+                 <annualData><double>999.5</double></annualData></domain.web.AnnualGcmDatum></list>",
+            )
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        assert!(
+            (climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap()
+                - 1000.0)
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_historical_rainfall_map() {
+        let server = StubServer::start(|request| {
+            if request_path(request).contains("/1920/1939/") {
+                xml_response("<list/>")
+            } else {
+                xml_response(&annual_gcm_xml(&[900.0, 1000.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let map = climate_api.get_historical_rainfall_map("gbr");
+
+        assert_eq!(
+            map.keys().cloned().collect::<Vec<_>>(),
+            vec![(1920, 1939), (1940, 1959), (1960, 1979), (1980, 1999)]
+        );
+        match &map[&(1920, 1939)] {
+            Err(Error::DateRangeNotSupported(1920, 1939)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert!((map[&(1980, 1999)].as_ref().unwrap() - 950.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_connect_error_message_contains_url() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name("http://127.0.0.1:1")
+            .build();
+
+        let err = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap_err();
+
+        assert!(err.to_string().contains(
+            "http://127.0.0.1:1/climateweb/rest/v1/country/annualavg/pr/1980/1999/gbr.xml"
+        ));
+    }
+
+    #[test]
+    fn test_deserialization_error_message_contains_url() {
+        let server = StubServer::start(|_| xml_response("<list><domain.web.AnnualGcmDatum>"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let err = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap_err();
+        let url = format!(
+            "{}/climateweb/rest/v1/country/annualavg/pr/1980/1999/gbr.xml",
+            server.url()
+        );
+
+        match &err {
+            Error::Deserialization(_, _) => (),
+            _ => panic!("The function returned a wrong error: {}", err),
+        }
+        assert!(err.to_string().contains(&url));
+    }
+
+    #[test]
+    fn test_supported_countries_include_great_britain() {
+        let countries = ClimateApiClient::new().get_supported_countries().unwrap();
+
+        assert!(!countries.is_empty());
+        assert!(countries.iter().any(|country| country.iso3 == "gbr"));
+    }
+
+    #[test]
+    fn test_default_headers_are_sent() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("secret"));
+        headers.insert("x-client-name", HeaderValue::from_static("climate-tck"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_default_headers(headers)
+            .build();
+
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        let request = server.requests()[0].to_lowercase();
+
+        assert!(request.contains("x-api-key: secret"));
+        assert!(request.contains("x-client-name: climate-tck"));
+    }
+
+    #[test]
+    fn test_annual_rainfall_aggregated_with_trimmed_mean() {
+        let server =
+            StubServer::start(|_| xml_response(&annual_gcm_xml(&[1.0, 10.0, 20.0, 30.0, 1000.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+        let trimmed_mean = |values: &[f64]| {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let trimmed = &sorted[1..sorted.len() - 1];
+
+            trimmed.iter().sum::<f64>() / trimmed.len() as f64
+        };
+
+        let result = climate_api
+            .get_annual_rainfall_aggregated(1980, 1999, "gbr", trimmed_mean)
+            .unwrap();
+
+        assert!((result - 20.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_empty_results_map_to_no_data_for_country() {
+        let data = AnnualGcmData {
+            results: Some(vec![]),
+        };
+
+        match extract_results(data, 1980, 1999, "ata") {
+            Err(Error::NoDataForCountry(country_iso)) => assert_eq!(country_iso, "ata"),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_source_window_of_average_rainfall_for_great_britain_from_1980_to_1999_direct() {
+        test_source_window_of_average_rainfall_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_source_window_of_average_rainfall_for_great_britain_from_1980_to_1999_playback() {
+        test_source_window_of_average_rainfall_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_source_window_of_average_rainfall_for_great_britain_from_1980_to_1999_record() {
+        test_source_window_of_average_rainfall_for_great_britain_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    fn test_source_window_of_average_rainfall_for_great_britain_from_1980_to_1999(
+        climate_api: ClimateApiClient,
+    ) {
+        let (average, source_window) = climate_api
+            .get_average_annual_rainfall_with_source_window(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((average - 988.8454972331015).abs() < f64::EPSILON);
+        assert_eq!(source_window, (1980, 1999));
+    }
+
+    #[test]
+    fn test_rate_limit_spreads_requests() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_rate_limit(10.0)
+            .build();
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap();
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_rate_limit_wait_doesnt_outlast_deadline() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_rate_limit(0.5)
+            .build();
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        let start = Instant::now();
+        let result = climate_api.get_average_annual_rainfall_by(
+            start + Duration::from_millis(200),
+            1980,
+            1999,
+            "gbr",
+        );
+
+        assert!(matches!(result, Err(Error::DeadlineExceeded)));
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_non_positive_rate_limit_turns_limit_off() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_rate_limit(0.0)
+            .build();
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap();
+        }
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_rainfall_stddev_for_great_britain_from_1980_to_1999_direct() {
+        test_rainfall_stddev_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rainfall_stddev_for_great_britain_from_1980_to_1999_playback() {
+        test_rainfall_stddev_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rainfall_stddev_for_great_britain_from_1980_to_1999_record() {
+        test_rainfall_stddev_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    fn test_rainfall_stddev_for_great_britain_from_1980_to_1999(climate_api: ClimateApiClient) {
+        let stddev = climate_api.get_rainfall_stddev(1980, 1999, "gbr").unwrap();
+
+        assert!((stddev - 102.7553189921046).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_config_summary_reports_disabled_certificate_verification() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_danger_accept_invalid_certs(true)
+            .build();
+
+        assert!(climate_api
+            .config_summary()
+            .contains("certificate verification: disabled"));
+    }
+
+    #[test]
+    fn test_great_britain_is_wetter_than_egypt_from_1980_to_1999_direct() {
+        test_great_britain_is_wetter_than_egypt_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_And_Egypt_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_great_britain_is_wetter_than_egypt_from_1980_to_1999_playback() {
+        test_great_britain_is_wetter_than_egypt_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_And_Egypt_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_great_britain_is_wetter_than_egypt_from_1980_to_1999_record() {
+        test_great_britain_is_wetter_than_egypt_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    fn test_great_britain_is_wetter_than_egypt_from_1980_to_1999(climate_api: ClimateApiClient) {
+        assert!(climate_api.is_wetter(1980, 1999, "gbr", "egy").unwrap());
+    }
+
+    #[test]
+    fn test_interpolated_annual_rainfall_between_1960_and_1980() {
+        let server = StubServer::start(|request| {
+            if request_path(request).contains("/1960/1979/") {
+                xml_response(&annual_gcm_xml(&[100.0]))
+            } else {
+                xml_response(&annual_gcm_xml(&[200.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let rainfall = climate_api
+            .get_interpolated_annual_rainfall(1970, 1989, "gbr")
+            .unwrap();
+
+        assert!((rainfall - 150.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_annual_rainfall_json_for_great_britain_from_1980_to_1999_direct() {
+        test_annual_rainfall_json_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_annual_rainfall_json_for_great_britain_from_1980_to_1999_playback() {
+        test_annual_rainfall_json_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_annual_rainfall_json_for_great_britain_from_1980_to_1999_record() {
+        test_annual_rainfall_json_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    fn test_annual_rainfall_json_for_great_britain_from_1980_to_1999(
+        climate_api: ClimateApiClient,
+    ) {
+        let json = climate_api
+            .get_annual_rainfall_json(1980, 1999, "gbr")
+            .unwrap();
+
+        assert_eq!(json["country"], "gbr");
+        assert_eq!(json["from_year"], 1980);
+        assert_eq!(json["to_year"], 1999);
+        assert_eq!(json["model_count"], 15);
+        assert!((json["value"].as_f64().unwrap() - 988.8454972331015).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_build_with_domain_keeps_builder_as_template() {
+        let template = ClimateApiClientBuilder::new().with_etag_cache(true);
+
+        let first = template.build_with_domain("http://localhost:61417");
+        let second = template.build_with_domain("http://localhost:61418");
+
+        assert!(first
+            .config_summary()
+            .contains("domain: http://localhost:61417,"));
+        assert!(second
+            .config_summary()
+            .contains("domain: http://localhost:61418,"));
+        assert!(second.config_summary().contains("ETag cache: enabled"));
+    }
+
+    #[test]
+    fn test_combined_annual_averages_for_rainfall_and_temperature() {
+        let server = StubServer::start(|_| {
+            let datum = |gcm: &str, variable: &str, value: f64| {
+                format!(
+                    "<domain.web.AnnualGcmDatum><gcm>{}</gcm><variable>{}</variable>\
+                     <fromYear>1980</fromYear><toYear>1999</toYear>\
+                     <annualData><double>{}</double></annualData></domain.web.AnnualGcmDatum>",
+                    gcm, variable, value
+                )
+            };
+
+            xml_response(&format!(
+                "<list>{}{}{}{}</list>",
+                datum("gcm_0", "pr", 900.0),
+                datum("gcm_1", "pr", 1000.0),
+                datum("gcm_0", "tas", 8.5),
+                datum("gcm_1", "tas", 9.5)
+            ))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let averages = climate_api
+            .get_combined_annual_averages(&["pr", "tas"], 1980, 1999, "gbr")
+            .unwrap();
+
+        assert_eq!(averages.len(), 2);
+        assert!((averages["pr"] - 950.0).abs() < f64::EPSILON);
+        assert!((averages["tas"] - 9.0).abs() < f64::EPSILON);
+        assert_eq!(
+            request_path(&server.requests()[0]),
+            "/climateweb/rest/v1/country/annualavg/pr,tas/1980/1999/gbr.xml"
+        );
+    }
+
+    #[test]
+    fn test_validate_request_reports_all_problems() {
+        assert_eq!(
+            ClimateApiClient::validate_request(1980, 1999, "gbr"),
+            Ok(())
+        );
+        assert_eq!(
+            ClimateApiClient::validate_request(1985, 2004, "Middle Earth"),
+            Err(vec![
+                ValidationError::StartYearNotSupported(1985),
+                ValidationError::MalformedCountryCode(String::from("Middle Earth")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_request_is_opt_in_for_fetch_methods() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        assert!(ClimateApiClient::validate_request(1980, 1999, "gb").is_err());
+        assert!(climate_api
+            .get_average_annual_rainfall(1980, 1999, "gb")
+            .is_ok());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_historical_mean_rainfall_skips_windows_without_data() {
+        let server = StubServer::start(|request| {
+            let path = request_path(request);
+
+            if path.contains("/1920/1939/") {
+                xml_response(&annual_gcm_xml(&[100.0]))
+            } else if path.contains("/1940/1959/") {
+                xml_response("<list/>")
+            } else if path.contains("/1960/1979/") {
+                xml_response(&annual_gcm_xml(&[200.0]))
+            } else {
+                xml_response(&annual_gcm_xml(&[300.0, 500.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let mean = climate_api.get_historical_mean_rainfall("gbr").unwrap();
+
+        assert!((mean - 700.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_retry_budget_caps_retries_across_batch() {
+        let server = StubServer::start(|_| response("503 Service Unavailable", &[], ""));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_max_retries(3)
+            .with_retry_budget(2, 0.0)
+            .build();
+
+        let results = climate_api.get_average_annual_rainfall_many(
+            1980,
+            1999,
+            &["gbr", "fra", "egy"],
+            &AtomicBool::new(false),
+        );
+
+        assert!(results.iter().all(|(_, result)| result.is_err()));
+        assert_eq!(server.requests().len(), 3 + 2);
+    }
+
+    #[test]
+    fn test_retry_budget_with_negative_or_nan_rate_never_refills() {
+        for retries_per_second in &[-1.0, f64::NAN] {
+            let server = StubServer::start(|_| response("503 Service Unavailable", &[], ""));
+            let climate_api = ClimateApiClientBuilder::new()
+                .with_domain_name(server.url())
+                .with_max_retries(3)
+                .with_retry_budget(1, *retries_per_second)
+                .build();
+
+            for _ in 0..2 {
+                assert!(climate_api
+                    .get_average_annual_rainfall(1980, 1999, "gbr")
+                    .is_err());
+            }
+
+            assert_eq!(server.requests().len(), 2 + 1);
+        }
+    }
+
+    #[test]
+    fn test_http_client_can_send_requests() {
+        let server = StubServer::start(|_| xml_response("<list/>"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let response = climate_api
+            .http_client()
+            .get(&format!("{}/status", server.url()))
+            .send()
+            .unwrap();
+
+        assert!(response.status().is_success());
+        assert_eq!(request_path(&server.requests()[0]), "/status");
+    }
+
+    #[test]
+    fn test_average_rainfall_by_deadline_returns_when_deadline_passes() {
+        let server = StubServer::start(|_| {
+            thread::sleep(Duration::from_secs(2));
+
+            xml_response(&annual_gcm_xml(&[10.0]))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_max_retries(3)
+            .build();
+
+        let start = Instant::now();
+        let result = climate_api.get_average_annual_rainfall_by(
+            start + Duration::from_millis(200),
+            1980,
+            1999,
+            "gbr",
+        );
+
+        match result {
+            Err(Error::DeadlineExceeded) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_average_rainfall_batch_returns_results_in_input_order() {
+        let server = StubServer::start(|request| {
+            let path = request_path(request);
+
+            if path.ends_with("/xxx.xml") {
+                xml_response("Invalid country code. Three letters are required")
+            } else if path.contains("/1985/1995/") {
+                xml_response("<list/>")
+            } else {
+                xml_response(&annual_gcm_xml(&[10.0, 20.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let results = climate_api.get_average_annual_rainfall_batch(&[
+            (String::from("gbr"), 1980, 1999),
+            (String::from("xxx"), 1980, 1999),
+            (String::from("gbr"), 1985, 1995),
+            (String::from("fra"), 1960, 1979),
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert!((results[0].as_ref().unwrap() - 15.0).abs() < f64::EPSILON);
+        match &results[1] {
+            Err(Error::NotRecognizedByClimateWeb) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        match &results[2] {
+            Err(Error::DateRangeNotSupported(1985, 1995)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert!((results[3].as_ref().unwrap() - 15.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_average_and_standard_deviation_of_large_values() {
+        let data: Vec<AnnualGcmDatum> = [1e16 + 2.0, 1e16 - 2.0, 1e16]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| AnnualGcmDatum {
+                gcm: format!("gcm_{}", i),
+                variable: String::from("pr"),
+                from_year: String::from("1980"),
+                to_year: String::from("1999"),
+                scenario: None,
+                annual_data: AnnualData { double: *value },
+            })
+            .collect();
+
+        assert!((average(&data) - 1e16).abs() < f64::EPSILON);
+        assert!((standard_deviation(&data) - (8.0_f64 / 3.0).sqrt()).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_mean_sums_left_to_right() {
+        let mean = mean(vec![1e16, 1.0, 1.0, -1e16]).unwrap();
+
+        assert!(mean.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_mean_of_no_values_is_none() {
+        assert_eq!(mean(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_monthly_rainfall_anomaly_for_future_window() {
+        let server = StubServer::start(|_| {
+            let datum = |gcm: &str, offset: f64| {
+                let month_vals: String = (1..=12)
+                    .map(|month| format!("<double>{}</double>", f64::from(month) + offset))
+                    .collect();
+
+                format!(
+                    "<domain.web.MonthlyGcmDatum><gcm>{}</gcm><variable>pr</variable>\
+                     <scenario>a2</scenario><fromYear>2020</fromYear><toYear>2039</toYear>\
+                     <monthVals>{}</monthVals></domain.web.MonthlyGcmDatum>",
+                    gcm, month_vals
+                )
+            };
+
+            xml_response(&format!(
+                "<list>{}{}</list>",
+                datum("gcm_0", -1.0),
+                datum("gcm_1", 1.0)
+            ))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let anomalies = climate_api
+            .get_monthly_rainfall_anomaly(2020, 2039, "gbr")
+            .unwrap();
+
+        assert_eq!(
+            anomalies,
+            [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0]
+        );
+        assert_eq!(
+            request_path(&server.requests()[0]),
+            "/climateweb/rest/v1/country/manom/pr/2020/2039/gbr.xml"
+        );
+    }
+
+    #[test]
+    fn test_monthly_rainfall_anomaly_rejects_historical_window() {
+        match ClimateApiClient::new().get_monthly_rainfall_anomaly(1980, 1999, "gbr") {
+            Err(Error::DateRangeNotSupported(1980, 1999)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_body_text_country_error_detection() {
+        let server =
+            StubServer::start(|_| xml_response("Invalid country code. Three letters are required"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_country_error_detection(CountryErrorDetection::BodyText)
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1980, 1999, "Middle Earth") {
+            Err(Error::NotRecognizedByClimateWeb) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_shape_country_error_detection() {
+        let server = StubServer::start(|_| xml_response("Unknown country"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_country_error_detection(CountryErrorDetection::Shape)
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1980, 1999, "Middle Earth") {
+            Err(Error::NotRecognizedByClimateWeb) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_status_country_error_detection() {
+        let server = StubServer::start(|_| response("404 Not Found", &[], ""));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_country_error_detection(CountryErrorDetection::Status)
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1980, 1999, "Middle Earth") {
+            Err(Error::NotRecognizedByClimateWeb) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_rainfall_cv_for_great_britain_from_1980_to_1999_direct() {
+        test_rainfall_cv_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rainfall_cv_for_great_britain_from_1980_to_1999_playback() {
+        test_rainfall_cv_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rainfall_cv_for_great_britain_from_1980_to_1999_record() {
+        test_rainfall_cv_for_great_britain_from_1980_to_1999(ClimateApiClient::new());
+    }
+
+    fn test_rainfall_cv_for_great_britain_from_1980_to_1999(climate_api: ClimateApiClient) {
+        let cv = climate_api.get_rainfall_cv(1980, 1999, "gbr").unwrap();
+
+        assert!(cv > 0.1 && cv < 0.11);
+    }
+
+    #[test]
+    fn test_disk_cache_serves_repeated_request() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0, 20.0])));
+        let cache_directory = TempDir::new("worldbank_climate_disk_cache_test");
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_disk_cache(cache_directory.path().to_path_buf())
+            .build();
+
+        let first = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+        let second = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((first - 15.0).abs() < f64::EPSILON);
+        assert!((second - 15.0).abs() < f64::EPSILON);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_disk_cache_ignores_expired_entries() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0, 20.0])));
+        let cache_directory = TempDir::new("worldbank_climate_disk_cache_ttl_test");
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_disk_cache(cache_directory.path().to_path_buf())
+            .with_disk_cache_ttl(Duration::from_secs(0))
+            .build();
+
+        for _ in 0..2 {
+            climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap();
+        }
+
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_disk_cache_keeps_fallback_responses_off_the_primary_domain() {
+        let primary_down = Arc::new(AtomicBool::new(true));
+        let primary_state = Arc::clone(&primary_down);
+        let primary = StubServer::start(move |_| {
+            if primary_state.load(Ordering::SeqCst) {
+                String::new()
+            } else {
+                xml_response(&annual_gcm_xml(&[10.0, 20.0]))
+            }
+        });
+        let fallback = StubServer::start(|_| xml_response(&annual_gcm_xml(&[30.0, 50.0])));
+        let cache_directory = TempDir::new("worldbank_climate_disk_cache_fallback_test");
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(primary.url())
+            .with_fallback_domain(fallback.url())
+            .with_disk_cache(cache_directory.path().to_path_buf())
+            .build();
+
+        let from_fallback = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+        primary_down.store(false, Ordering::SeqCst);
+        let from_primary = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((from_fallback - 40.0).abs() < f64::EPSILON);
+        assert!((from_primary - 15.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_header_provider_is_invoked_per_request() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let counter = AtomicUsize::new(0);
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_header_provider(Arc::new(move || {
+                let token = counter.fetch_add(1, Ordering::SeqCst) + 1;
+
+                vec![(
+                    String::from("authorization"),
+                    format!("Bearer token-{}", token),
+                )]
+            }))
+            .build();
+
+        for _ in 0..2 {
+            climate_api
+                .get_average_annual_rainfall(1980, 1999, "gbr")
+                .unwrap();
+        }
+
+        let requests = server.requests();
+
+        assert!(requests[0]
+            .to_lowercase()
+            .contains("authorization: bearer token-1"));
+        assert!(requests[1]
+            .to_lowercase()
+            .contains("authorization: bearer token-2"));
+    }
+
+    #[test]
+    fn test_wettest_country_from_1980_to_1999() {
+        let fixture_body = |name: &str| {
+            let markdown = fs::read_to_string(format!(
+                "playback_data/average_Rainfall_For_{}_From_1980_to_1999_Exists.md",
+                name
+            ))
+            .unwrap();
+
+            read_response_body(&markdown).unwrap()
+        };
+        let gbr = fixture_body("Great_Britain");
+        let fra = fixture_body("France");
+        let egy = fixture_body("Egypt");
+        let server = StubServer::start(move |request| {
+            let path = request_path(request);
+
+            if path.ends_with("/gbr.xml") {
+                xml_response(&gbr)
+            } else if path.ends_with("/fra.xml") {
+                xml_response(&fra)
+            } else if path.ends_with("/egy.xml") {
+                xml_response(&egy)
+            } else {
+                xml_response("Invalid country code. Three letters are required")
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let (country_iso, rainfall) = climate_api
+            .wettest_country(1980, 1999, &["fra", "Middle Earth", "gbr", "egy"])
+            .unwrap();
+
+        assert_eq!(country_iso, "gbr");
+        assert!((rainfall - 988.8454972331015).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_domain_name_with_trailing_slash_is_normalized() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(format!("{}/", server.url()))
+            .build();
+
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert_eq!(
+            request_path(&server.requests()[0]),
+            "/climateweb/rest/v1/country/annualavg/pr/1980/1999/gbr.xml"
+        );
+    }
+
+    #[test]
+    fn test_domain_name_path_is_kept_as_prefix() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(format!("{}/mirror/api/", server.url()))
+            .build();
+
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert_eq!(
+            request_path(&server.requests()[0]),
+            "/mirror/api/climateweb/rest/v1/country/annualavg/pr/1980/1999/gbr.xml"
+        );
+    }
+
+    #[test]
+    fn test_weighted_average_annual_rainfall() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[100.0, 200.0, 300.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+        let weights: HashMap<String, f64> =
+            vec![(String::from("gcm_0"), 1.0), (String::from("gcm_2"), 3.0)]
+                .into_iter()
+                .collect();
+
+        let defaulted = climate_api
+            .get_weighted_average_annual_rainfall(1980, 1999, "gbr", &weights, false)
+            .unwrap();
+        let skipped = climate_api
+            .get_weighted_average_annual_rainfall(1980, 1999, "gbr", &weights, true)
+            .unwrap();
+
+        assert!((defaulted - 240.0).abs() < f64::EPSILON);
+        assert!((skipped - 250.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_non_finite_value_is_rejected() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0, f64::NAN])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1980, 1999, "gbr") {
+            Err(Error::NonFiniteValue(gcm)) => assert_eq!(gcm, "gcm_1"),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_rainfall_classified_for_great_britain_and_egypt_from_1980_to_1999_direct() {
+        test_rainfall_classified_for_great_britain_and_egypt_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_playback_test(
+        "playback_data/average_Rainfall_For_Great_Britain_And_Egypt_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rainfall_classified_for_great_britain_and_egypt_from_1980_to_1999_playback() {
+        test_rainfall_classified_for_great_britain_and_egypt_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    #[test]
+    #[servirtium_record_test(
+        "playback_data/average_Rainfall_For_Great_Britain_And_Egypt_From_1980_to_1999_Exists.md",
+        servirtium_configure
+    )]
+    fn test_rainfall_classified_for_great_britain_and_egypt_from_1980_to_1999_record() {
+        test_rainfall_classified_for_great_britain_and_egypt_from_1980_to_1999(
+            ClimateApiClient::new(),
+        );
+    }
+
+    fn test_rainfall_classified_for_great_britain_and_egypt_from_1980_to_1999(
+        climate_api: ClimateApiClient,
+    ) {
+        let (_, gbr) = climate_api
+            .get_rainfall_classified(1980, 1999, "gbr")
+            .unwrap();
+        let (_, egy) = climate_api
+            .get_rainfall_classified(1980, 1999, "egy")
+            .unwrap();
+
+        assert_eq!(gbr, RainfallClass::Wet);
+        assert_eq!(egy, RainfallClass::Arid);
+    }
+
+    #[test]
+    fn test_resolve_maps_host_to_address() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let address: SocketAddr = server.url().trim_start_matches("http://").parse().unwrap();
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(format!("http://climate.example:{}", address.port()))
+            .with_resolve("climate.example", address)
+            .build();
+
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!(server.requests()[0]
+            .to_lowercase()
+            .contains(&format!("host: climate.example:{}", address.port())));
+    }
+
+    #[test]
+    fn test_annual_rainfall_model_map_suffixes_duplicate_models() {
+        let server = StubServer::start(|_| {
+            let datum = |gcm: &str, value: f64| {
+                format!(
+                    "<domain.web.AnnualGcmDatum><gcm>{}</gcm><variable>pr</variable>\
+                     <fromYear>1980</fromYear><toYear>1999</toYear>\
+                     <annualData><double>{}</double></annualData></domain.web.AnnualGcmDatum>",
+                    gcm, value
+                )
+            };
+
+            xml_response(&format!(
+                "<list>{}{}{}</list>",
+                datum("bccr_bcm2_0", 990.5),
+                datum("cccma_cgcm3_1", 1010.25),
+                datum("bccr_bcm2_0", 1001.0)
+            ))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let model_map = climate_api
+            .get_annual_rainfall_model_map(1980, 1999, "gbr")
+            .unwrap();
+
+        assert_eq!(model_map.len(), 3);
+        assert!((model_map["cccma_cgcm3_1"] - 1010.25).abs() < f64::EPSILON);
+        assert!((model_map["bccr_bcm2_0"] - 990.5).abs() < f64::EPSILON);
+        assert!((model_map["bccr_bcm2_0#2"] - 1001.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_partition_valid_countries() {
+        let (valid, invalid) =
+            ClimateApiClient::partition_valid_countries(&["gbr", "Middle Earth", "FRA", "xyz"]);
+
+        assert_eq!(valid, vec!["gbr", "FRA"]);
+        assert_eq!(invalid, vec!["Middle Earth", "xyz"]);
+    }
+
+    #[test]
+    fn test_last_modified_cache_serves_cached_average_on_not_modified() {
+        let served = AtomicUsize::new(0);
+        let server = StubServer::start(move |_| match served.fetch_add(1, Ordering::SeqCst) {
+            0 => response(
+                "200 OK",
+                &[
+                    ("content-type", "application/xml"),
+                    ("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+                ],
+                &annual_gcm_xml(&[10.0, 20.0]),
+            ),
+            _ => response("304 Not Modified", &[], ""),
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_last_modified_cache(true)
+            .build();
+
+        let first = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+        let second = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((first - 15.0).abs() < f64::EPSILON);
+        assert!((second - 15.0).abs() < f64::EPSILON);
+        assert!(server.requests()[1]
+            .to_lowercase()
+            .contains("if-modified-since: wed, 21 oct 2015 07:28:00 gmt"));
+    }
+
+    #[test]
+    fn test_scenario_count_for_future_window() {
+        let server = StubServer::start(|_| {
+            let datum = |gcm: &str, scenario: &str, value: f64| {
+                format!(
+                    "<domain.web.AnnualGcmDatum><gcm>{}</gcm><variable>pr</variable>\
+                     <scenario>{}</scenario><fromYear>2020</fromYear><toYear>2039</toYear>\
+                     <annualData><double>{}</double></annualData></domain.web.AnnualGcmDatum>",
+                    gcm, scenario, value
+                )
+            };
+
+            xml_response(&format!(
+                "<list>{}{}{}{}</list>",
+                datum("gcm_0", "a2", 900.0),
+                datum("gcm_0", "b1", 950.0),
+                datum("gcm_1", "a2", 1000.0),
+                datum("gcm_1", "b1", 1050.0)
+            ))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let count = climate_api.get_scenario_count(2020, 2039, "gbr").unwrap();
+
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_retry_delays_fall_within_jittered_range() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_max_retries(5)
+            .with_retry_jitter(0.5)
+            .with_retry_jitter_seed(42)
+            .build();
+        let same_seed = ClimateApiClientBuilder::new()
+            .with_max_retries(5)
+            .with_retry_jitter(0.5)
+            .with_retry_jitter_seed(42)
+            .build();
+
+        for retries in 0..5 {
+            let full_delay = RETRY_BASE_DELAY * 2u32.pow(retries);
+            let delay = climate_api.retry_delay(retries);
+
+            assert!(delay >= full_delay / 2 && delay <= full_delay);
+            assert_eq!(delay, same_seed.retry_delay(retries));
+        }
+    }
+
+    #[test]
+    fn test_retry_delays_are_not_jittered_when_disabled() {
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_max_retries(3)
+            .with_retry_jitter(0.0)
+            .build();
+
+        assert_eq!(climate_api.retry_delay(2), RETRY_BASE_DELAY * 4);
+    }
+
+    #[test]
+    fn test_retry_jitter_out_of_range_is_clamped() {
+        let negative = ClimateApiClientBuilder::new()
+            .with_max_retries(3)
+            .with_retry_jitter(-0.5)
+            .build();
+        let too_large = ClimateApiClientBuilder::new()
+            .with_max_retries(3)
+            .with_retry_jitter(2.0)
+            .with_retry_jitter_seed(42)
+            .build();
+
+        assert_eq!(negative.retry_delay(2), RETRY_BASE_DELAY * 4);
+        for retries in 0..3 {
+            assert!(too_large.retry_delay(retries) <= RETRY_BASE_DELAY * 2u32.pow(retries));
+        }
+    }
+
+    #[test]
+    fn test_default_builder_matches_new() {
+        let default = ClimateApiClientBuilder::default();
+        let new = ClimateApiClientBuilder::new();
+
+        assert_eq!(format!("{:?}", default), format!("{:?}", new));
+    }
+
+    #[test]
+    fn test_average_rainfall_if_available_is_none_without_model_data() {
+        let server = StubServer::start(|_| xml_response("<list/>"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let rainfall = climate_api
+            .get_average_annual_rainfall_if_available(1980, 1999, "ata")
+            .unwrap();
+
+        assert_eq!(rainfall, None);
+    }
 
-        let data: AnnualGcmData = quick_xml::de::from_str(&response_text)?;
-        let data = match data.results {
-            Some(data) => data,
-            None => return Err(Error::DateRangeNotSupported(from_year, to_year)),
-        };
+    #[test]
+    fn test_average_rainfall_if_available_keeps_genuine_zero_average() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[0.0, 0.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
 
-        let (sum, count) = data.into_iter().fold((0.0, 0), |(sum, count), datum| {
-            (sum + datum.annual_data.double, count + 1)
-        });
+        let rainfall = climate_api
+            .get_average_annual_rainfall_if_available(1980, 1999, "egy")
+            .unwrap();
 
-        Ok(match count {
-            0 => 0.0,
-            _ => sum / count as f64,
-        })
+        assert_eq!(rainfall, Some(0.0));
     }
 
-    pub fn get_average_annual_rainfall_for_two<T1: AsRef<str>, T2: AsRef<str>>(
-        &self,
-        from_year: u16,
-        to_year: u16,
-        country_iso_first: T1,
-        country_iso_second: T2,
-    ) -> Result<(f64, f64), Error> {
-        let first = self.get_average_annual_rainfall(from_year, to_year, country_iso_first)?;
-        let second = self.get_average_annual_rainfall(from_year, to_year, country_iso_second)?;
+    #[test]
+    fn test_average_rainfall_if_available_rejects_unsupported_window() {
+        let server = StubServer::start(|_| xml_response("<list/>"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
 
-        Ok((first, second))
+        match climate_api.get_average_annual_rainfall_if_available(1985, 1995, "gbr") {
+            Err(Error::DateRangeNotSupported(from_year, to_year)) => {
+                assert_eq!((from_year, to_year), (1985, 1995))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
-    fn construct_get_average_annual_rainfall_url<T: AsRef<str>>(
-        &self,
-        from_year: u16,
-        to_year: u16,
-        country_iso: T,
-    ) -> String {
-        format!(
-            "{}/climateweb/rest/v1/country/annualavg/pr/{}/{}/{}.xml",
-            self.domain_name,
-            from_year,
-            to_year,
-            country_iso.as_ref()
+    #[test]
+    fn test_streaming_average_matches_struct_average_on_great_britain_fixture() {
+        let markdown = fs::read_to_string(
+            "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
         )
+        .unwrap();
+        let body = read_response_body(&markdown).unwrap();
+        let data: AnnualGcmData = quick_xml::de::from_str(&body).unwrap();
+
+        let streamed = streaming_average(&body).unwrap().unwrap();
+
+        assert!((streamed - average(&data.results.unwrap())).abs() < f64::EPSILON);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{error::Error, ClimateApiClient};
-    use servirtium::{servirtium_playback_test, servirtium_record_test, ServirtiumConfiguration};
+    #[test]
+    fn test_average_rainfall_streaming_from_stub() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0, 20.0, 60.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
 
-    fn servirtium_configure(config: &mut ServirtiumConfiguration) {
-        config.set_domain_name("https://servirtium.github.io/worldbank-climate-recordings");
-        config.set_fail_if_markdown_changed(true);
+        let rainfall = climate_api
+            .get_average_annual_rainfall_streaming(1980, 1999, "gbr")
+            .unwrap();
 
-        config.add_record_response_mutations(|builder| {
-            builder.remove_headers(vec!["set-cookie", "date"])
-        });
+        assert!((rainfall - 30.0).abs() < f64::EPSILON);
+    }
 
-        config.add_playback_response_mutations(|builder| {
-            builder.add_header("date", "Sun, 02 Aug 2020 09:53:31 GMT")
-        });
+    #[test]
+    fn test_average_rainfall_streaming_reports_unsupported_range() {
+        let server = StubServer::start(|_| xml_response("<list/>"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        match climate_api.get_average_annual_rainfall_streaming(1985, 1995, "gbr") {
+            Err(Error::DateRangeNotSupported(from_year, to_year)) => {
+                assert_eq!((from_year, to_year), (1985, 1995))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
     #[test]
-    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists_direct() {
-        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_great_britain_fixture_average_matches_parser() {
+        assert_fixture_average(
+            "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
+            1980,
+            1999,
+            "gbr",
+            988.8454972331015,
+        );
     }
 
     #[test]
-    #[servirtium_playback_test(
-        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists_playback() {
-        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_average_rainfall_for_two_names_the_failing_second_country() {
+        let server = StubServer::start(|request| {
+            if request_path(request).ends_with("/gbr.xml") {
+                xml_response(&annual_gcm_xml(&[10.0, 20.0]))
+            } else {
+                xml_response("Invalid country code. Three letters are required")
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        match climate_api.get_average_annual_rainfall_for_two(1980, 1999, "gbr", "mde") {
+            Err(Error::SecondCountry(country_iso, e)) => {
+                assert_eq!(country_iso, "mde");
+                assert!(matches!(*e, Error::NotRecognizedByClimateWeb));
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
     #[test]
-    #[servirtium_record_test(
-        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists_record() {
-        test_average_rainfall_for_great_britain_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_rolling_rainfall_over_two_windows() {
+        let server = StubServer::start(|request| {
+            let path = request_path(request);
+            let average = if path.contains("/1920/1939/") {
+                100.0
+            } else if path.contains("/1940/1959/") {
+                200.0
+            } else if path.contains("/1960/1979/") {
+                300.0
+            } else {
+                400.0
+            };
+
+            xml_response(&annual_gcm_xml(&[average]))
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let rolling = climate_api.get_rolling_rainfall("gbr", 2).unwrap();
+
+        let expected = vec![
+            ((1920, 1959), 150.0),
+            ((1940, 1979), 250.0),
+            ((1960, 1999), 350.0),
+        ];
+        assert_eq!(rolling.into_iter().collect::<Vec<_>>(), expected);
     }
 
-    fn test_average_rainfall_for_great_britain_from_1980_to_1999_exists(
-        climate_api: ClimateApiClient,
-    ) {
-        assert!(
-            (climate_api
-                .get_average_annual_rainfall(1980, 1999, "gbr")
-                .unwrap()
-                - 988.8454972331015)
-                .abs()
-                < f64::EPSILON
-        );
+    #[test]
+    fn test_rolling_rainfall_is_empty_with_fewer_windows_than_requested() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let historical_windows = YearWindow::historical().len();
+        let rolling = climate_api
+            .get_rolling_rainfall("gbr", historical_windows + 1)
+            .unwrap();
+
+        assert!(rolling.is_empty());
     }
 
     #[test]
-    fn test_average_rainfall_for_france_from_1980_to_1999_exists_direct() {
-        test_average_rainfall_for_france_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_rolling_rainfall_rejects_zero_window_count() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[10.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        match climate_api.get_rolling_rainfall("gbr", 0) {
+            Err(Error::ZeroWindowCount) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert!(server.requests().is_empty());
     }
 
     #[test]
-    #[servirtium_playback_test(
-        "playback_data/average_Rainfall_For_France_From_1980_to_1999_Exists.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_france_from_1980_to_1999_exists_playback() {
-        test_average_rainfall_for_france_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_unsupported_range_predicate_maps_message_to_date_range_not_supported() {
+        let server = StubServer::start(|_| xml_response("No data for the requested years"));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .with_unsupported_range_predicate(Arc::new(|body: &str, status: u16| {
+                status == 200 && body.starts_with("No data for the requested years")
+            }))
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1985, 1995, "gbr") {
+            Err(Error::DateRangeNotSupported(from_year, to_year)) => {
+                assert_eq!((from_year, to_year), (1985, 1995))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
     #[test]
-    #[servirtium_record_test(
-        "playback_data/average_Rainfall_For_France_From_1980_to_1999_Exists.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_france_from_1980_to_1999_exists_record() {
-        test_average_rainfall_for_france_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_typed_average_rainfall_converts_to_inches() {
+        let server = StubServer::start(|_| xml_response(&annual_gcm_xml(&[254.0, 508.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let rainfall = climate_api
+            .get_average_annual_rainfall_typed(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((rainfall.millimeters() - 381.0).abs() < f64::EPSILON);
+        assert!((rainfall.inches() - 15.0).abs() < f64::EPSILON);
     }
 
-    fn test_average_rainfall_for_france_from_1980_to_1999_exists(climate_api: ClimateApiClient) {
-        assert!(
-            (climate_api
-                .get_average_annual_rainfall(1980, 1999, "fra")
-                .unwrap()
-                - 913.7986955122727)
-                .abs()
-                < f64::EPSILON
-        );
+    #[test]
+    fn test_capture_fixture_from_two_threads_writes_valid_files() {
+        let server = StubServer::start(|request| {
+            if request_path(request).ends_with("/gbr.xml") {
+                xml_response(&annual_gcm_xml(&[10.0, 20.0]))
+            } else {
+                xml_response(&annual_gcm_xml(&[30.0, 50.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+        let temp_dir = TempDir::new("worldbank_climate_capture_threads_test");
+        let fixtures = [
+            ("gbr", temp_dir.path().join("gbr.md"), 15.0),
+            ("fra", temp_dir.path().join("fra.md"), 40.0),
+        ];
+
+        thread::scope(|scope| {
+            for (country_iso, out_path, _) in &fixtures {
+                let climate_api = &climate_api;
+                scope.spawn(move || {
+                    climate_api
+                        .capture_fixture(1980, 1999, country_iso, out_path)
+                        .unwrap()
+                });
+            }
+        });
+
+        for (_, out_path, expected) in &fixtures {
+            let markdown = fs::read_to_string(out_path).unwrap();
+            let body = read_response_body(&markdown).unwrap();
+            let data: AnnualGcmData = quick_xml::de::from_str(&body).unwrap();
+
+            assert!((average(&data.results.unwrap()) - expected).abs() < f64::EPSILON);
+        }
     }
 
     #[test]
-    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists_direct() {
-        test_average_rainfall_for_egypt_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_latest_rainfall_for_great_britain_direct() {
+        test_latest_rainfall_for_great_britain(ClimateApiClient::new());
     }
 
     #[test]
     #[servirtium_playback_test(
-        "playback_data/average_Rainfall_For_Egypt_From_1980_to_1999_Exists.md",
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
         servirtium_configure
     )]
-    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists_playback() {
-        test_average_rainfall_for_egypt_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_latest_rainfall_for_great_britain_playback() {
+        test_latest_rainfall_for_great_britain(ClimateApiClient::new());
     }
 
     #[test]
     #[servirtium_record_test(
-        "playback_data/average_Rainfall_For_Egypt_From_1980_to_1999_Exists.md",
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
         servirtium_configure
     )]
-    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists_record() {
-        test_average_rainfall_for_egypt_from_1980_to_1999_exists(ClimateApiClient::new());
+    fn test_latest_rainfall_for_great_britain_record() {
+        test_latest_rainfall_for_great_britain(ClimateApiClient::new());
     }
 
-    fn test_average_rainfall_for_egypt_from_1980_to_1999_exists(climate_api: ClimateApiClient) {
-        assert!(
-            (climate_api
-                .get_average_annual_rainfall(1980, 1999, "egy")
-                .unwrap()
-                - 54.58587712129825)
-                .abs()
-                < f64::EPSILON
-        );
+    fn test_latest_rainfall_for_great_britain(climate_api: ClimateApiClient) {
+        let (window, rainfall) = climate_api.get_latest_annual_rainfall("gbr").unwrap();
+
+        assert_eq!(window, YearWindow::new(1980).unwrap());
+        assert!((rainfall - 988.8454972331015).abs() < f64::EPSILON);
     }
 
     #[test]
-    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist_direct() {
-        test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
-            ClimateApiClient::new(),
+    fn test_latest_rainfall_falls_back_to_earlier_window() {
+        let server = StubServer::start(|request| {
+            if request_path(request).contains("/1980/1999/") {
+                xml_response("<list/>")
+            } else {
+                xml_response(&annual_gcm_xml(&[500.0, 700.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let (window, rainfall) = climate_api.get_latest_annual_rainfall("gbr").unwrap();
+
+        assert_eq!(window, YearWindow::new(1960).unwrap());
+        assert!((rainfall - 600.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_default_window_average_rainfall_for_great_britain_direct() {
+        test_default_window_average_rainfall_for_great_britain(
+            ClimateApiClientBuilder::new()
+                .with_default_window(YearWindow::new(1980).unwrap())
+                .build(),
         );
     }
 
     #[test]
     #[servirtium_playback_test(
-        "playback_data/average_Rainfall_For_Great_Britain_From_1985_to_1995_Does_Not_Exist.md",
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
         servirtium_configure
     )]
-    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist_playback() {
-        test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
-            ClimateApiClient::new(),
+    fn test_default_window_average_rainfall_for_great_britain_playback() {
+        test_default_window_average_rainfall_for_great_britain(
+            ClimateApiClientBuilder::new()
+                .with_default_window(YearWindow::new(1980).unwrap())
+                .build(),
         );
     }
 
     #[test]
     #[servirtium_record_test(
-        "playback_data/average_Rainfall_For_Great_Britain_From_1985_to_1995_Does_Not_Exist.md",
+        "playback_data/average_Rainfall_For_Great_Britain_From_1980_to_1999_Exists.md",
         servirtium_configure
     )]
-    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist_record() {
-        test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
-            ClimateApiClient::new(),
+    fn test_default_window_average_rainfall_for_great_britain_record() {
+        test_default_window_average_rainfall_for_great_britain(
+            ClimateApiClientBuilder::new()
+                .with_default_window(YearWindow::new(1980).unwrap())
+                .build(),
         );
     }
 
-    fn test_average_rainfall_for_great_britain_from_1985_to_1995_does_not_exist(
-        climate_api: ClimateApiClient,
-    ) {
-        let result = climate_api.get_average_annual_rainfall(1985, 1995, "gbr");
+    fn test_default_window_average_rainfall_for_great_britain(climate_api: ClimateApiClient) {
+        let rainfall = climate_api
+            .get_average_annual_rainfall_default("gbr")
+            .unwrap();
 
-        match result {
-            Err(err) => match err {
-                Error::DateRangeNotSupported(1985, 1995) => (),
-                _ => panic!("The function returned a wrong error: {}", err),
-            },
-            _ => panic!("The function call should return an error"),
-        }
+        assert!((rainfall - 988.8454972331015).abs() < f64::EPSILON);
     }
 
     #[test]
-    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist_direct() {
-        test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
-            ClimateApiClient::new(),
-        );
+    fn test_average_rainfall_default_without_default_window() {
+        match ClimateApiClient::new().get_average_annual_rainfall_default("gbr") {
+            Err(Error::NoDefaultWindow) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
     #[test]
-    #[servirtium_playback_test(
-        "playback_data/average_Rainfall_For_Middle_Earth_From_1980_to_1999_Does_Not_Exist.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist_playback() {
-        test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
-            ClimateApiClient::new(),
-        );
+    fn test_unsupported_default_window_cannot_be_configured() {
+        match YearWindow::new(1985)
+            .map(|window| ClimateApiClientBuilder::new().with_default_window(window))
+        {
+            Err(Error::DateRangeNotSupported(from_year, to_year)) => {
+                assert_eq!((from_year, to_year), (1985, 2004))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
     #[test]
-    #[servirtium_record_test(
-        "playback_data/average_Rainfall_For_Middle_Earth_From_1980_to_1999_Does_Not_Exist.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist_record() {
-        test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
-            ClimateApiClient::new(),
-        );
-    }
+    fn test_most_uncertain_country_has_widest_model_spread() {
+        let server = StubServer::start(|request| {
+            let path = request_path(request);
 
-    fn test_average_rainfall_for_middle_earth_from_1980_to_1999_does_not_exist(
-        climate_api: ClimateApiClient,
-    ) {
-        let result = climate_api.get_average_annual_rainfall(1980, 1999, "mde");
+            if path.ends_with("/gbr.xml") {
+                xml_response(&annual_gcm_xml(&[900.0, 1000.0, 1100.0]))
+            } else if path.ends_with("/fra.xml") {
+                xml_response(&annual_gcm_xml(&[700.0, 800.0, 900.0]))
+            } else if path.ends_with("/egy.xml") {
+                xml_response(&annual_gcm_xml(&[10.0, 20.0, 60.0]))
+            } else {
+                xml_response("Invalid country code. Three letters are required")
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
 
-        match result {
-            Err(err) => match err {
-                Error::NotRecognizedByClimateWeb => (),
-                _ => panic!("The function returned a wrong error: {}", err),
-            },
-            _ => panic!("The function call should return an error"),
-        }
+        let (country_iso, cv) = climate_api
+            .most_uncertain_country(1980, 1999, &["gbr", "Middle Earth", "fra", "egy"])
+            .unwrap();
+
+        assert_eq!(country_iso, "egy");
+        assert!(cv > 0.7 && cv < 0.8);
     }
 
     #[test]
-    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist_direct() {
-        test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
-            ClimateApiClient::new(),
-        );
+    fn test_etag_cache_is_keyed_by_domain_and_path() {
+        let primary = StubServer::start(|request| {
+            if request.to_lowercase().contains("if-none-match") {
+                response("304 Not Modified", &[("etag", "\"primary\"")], "")
+            } else {
+                response(
+                    "200 OK",
+                    &[("content-type", "application/xml"), ("etag", "\"primary\"")],
+                    &annual_gcm_xml(&[10.0, 20.0]),
+                )
+            }
+        });
+        let fallback = StubServer::start(|_| xml_response(&annual_gcm_xml(&[30.0, 50.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(primary.url())
+            .with_fallback_domain(fallback.url())
+            .with_etag_cache(true)
+            .build();
+
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+        let unreachable_primary = ClimateApiClient {
+            domain_name: String::from("http://127.0.0.1:1"),
+            ..climate_api.clone()
+        };
+        let rainfall = unreachable_primary
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((rainfall - 40.0).abs() < f64::EPSILON);
+        assert!(!fallback.requests()[0]
+            .to_lowercase()
+            .contains("if-none-match"));
     }
 
     #[test]
-    #[servirtium_playback_test(
-        "playback_data/average_Rainfall_For_Great_Britain_And_France_From_1980_to_1999_Exists.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist_playback() {
-        test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
-            ClimateApiClient::new(),
-        );
+    fn test_etag_cache_strips_validators_when_falling_back() {
+        let primary = StubServer::start(|request| {
+            if request.to_lowercase().contains("if-none-match") {
+                String::new()
+            } else {
+                response(
+                    "200 OK",
+                    &[("content-type", "application/xml"), ("etag", "\"primary\"")],
+                    &annual_gcm_xml(&[10.0, 20.0]),
+                )
+            }
+        });
+        let fallback = StubServer::start(|_| xml_response(&annual_gcm_xml(&[30.0, 50.0])));
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(primary.url())
+            .with_fallback_domain(fallback.url())
+            .with_etag_cache(true)
+            .build();
+
+        climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+        let rainfall = climate_api
+            .get_average_annual_rainfall(1980, 1999, "gbr")
+            .unwrap();
+
+        assert!((rainfall - 40.0).abs() < f64::EPSILON);
+        assert!(!fallback.requests()[0]
+            .to_lowercase()
+            .contains("if-none-match"));
     }
 
     #[test]
-    #[servirtium_record_test(
-        "playback_data/average_Rainfall_For_Great_Britain_And_France_From_1980_to_1999_Exists.md",
-        servirtium_configure
-    )]
-    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist_record() {
-        test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
-            ClimateApiClient::new(),
-        );
+    fn test_failed_tls_handshake_maps_to_tls_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n");
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(format!("https://{}", address))
+            .build();
+
+        match climate_api.get_average_annual_rainfall(1980, 1999, "gbr") {
+            Err(Error::Tls(_)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
-    fn test_average_rainfall_for_great_britain_and_france_from_1980_to_1999_exist(
-        climate_api: ClimateApiClient,
-    ) {
-        let (gbr, fra) = climate_api
-            .get_average_annual_rainfall_for_two(1980, 1999, "gbr", "fra")
+    #[test]
+    fn test_verify_average_against_ensemble() {
+        let server = StubServer::start(|request| {
+            if request_path(request).contains("/ensemble/") {
+                xml_response(
+                    "<list><domain.web.AnnualEnsembleDatum><variable>pr</variable>\
+                     <fromYear>1980</fromYear><toYear>1999</toYear>\
+                     <annualData><double>20.5</double></annualData>\
+                     </domain.web.AnnualEnsembleDatum></list>",
+                )
+            } else {
+                xml_response(&annual_gcm_xml(&[10.0, 20.0, 30.0]))
+            }
+        });
+        let climate_api = ClimateApiClientBuilder::new()
+            .with_domain_name(server.url())
+            .build();
+
+        let (client_average, ensemble_average, difference) = climate_api
+            .verify_average_against_ensemble(1980, 1999, "gbr")
             .unwrap();
 
-        assert!((gbr - 988.8454972331015).abs() < f64::EPSILON);
-        assert!((fra - 913.7986955122727).abs() < f64::EPSILON);
+        assert!((client_average - 20.0).abs() < f64::EPSILON);
+        assert!((ensemble_average - 20.5).abs() < f64::EPSILON);
+        assert!((difference + 0.5).abs() < f64::EPSILON);
+        assert_eq!(
+            request_path(&server.requests()[1]),
+            "/climateweb/rest/v1/country/annualavg/ensemble/pr/1980/1999/gbr.xml"
+        );
     }
 }
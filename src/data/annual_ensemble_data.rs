@@ -0,0 +1,28 @@
+use crate::data::annual_gcm_data::AnnualData;
+use serde::{Deserialize, Serialize};
+
+/// Annual value the API computes across all Global Circulation Models (GCM) for a year interval.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnualEnsembleDatum {
+    /// Climate variable, e.g. `pr` for precipitation, serialized as `variable`.
+    pub variable: String,
+    /// Start of the year interval, serialized as `fromYear`.
+    pub from_year: String,
+    /// End of the year interval, serialized as `toYear`.
+    pub to_year: String,
+    /// Emission scenario of future data, e.g. `a2`, serialized as `scenario`. `None` for historical data.
+    pub scenario: Option<String>,
+    /// The ensemble value, serialized as `annualData`.
+    pub annual_data: AnnualData,
+}
+
+/// List of ensemble data returned by the annual average ensemble endpoint, one entry per emission scenario.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename = "list")]
+pub struct AnnualEnsembleData {
+    /// Ensemble results, serialized as `domain.web.AnnualEnsembleDatum`. `None` if the API has no data for the
+    /// requested year interval.
+    #[serde(rename = "domain.web.AnnualEnsembleDatum")]
+    pub results: Option<Vec<AnnualEnsembleDatum>>,
+}
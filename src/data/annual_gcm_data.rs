@@ -1,23 +1,64 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Annual value reported by a single Global Circulation Model.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct AnnualData {
+    /// Average annual value, serialized as `double`. The element names the API uses for other numeric types
+    /// (`float`, `int`, `long` and `big-decimal`) are accepted as well, as are integer values.
+    #[serde(alias = "float", alias = "int", alias = "long", alias = "big-decimal")]
     pub double: f64,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// Data reported by a single Global Circulation Model (GCM) for a year interval.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AnnualGcmDatum {
+    /// Name of the GCM, serialized as `gcm`.
     pub gcm: String,
+    /// Climate variable, e.g. `pr` for precipitation, serialized as `variable`.
     pub variable: String,
+    /// Start of the year interval, serialized as `fromYear`.
     pub from_year: String,
+    /// End of the year interval, serialized as `toYear`.
     pub to_year: String,
+    /// Emission scenario of future data, e.g. `a2`, serialized as `scenario`. `None` for historical data.
+    pub scenario: Option<String>,
+    /// The annual value, serialized as `annualData`.
     pub annual_data: AnnualData,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+/// List of per-GCM data returned by the annual average endpoint.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(rename = "list")]
 pub struct AnnualGcmData {
+    /// Per-GCM results, serialized as `domain.web.AnnualGcmDatum`. `None` if the API has no data for the
+    /// requested year interval.
     #[serde(rename = "domain.web.AnnualGcmDatum")]
     pub results: Option<Vec<AnnualGcmDatum>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AnnualData, AnnualGcmData, AnnualGcmDatum};
+
+    #[test]
+    fn test_annual_gcm_data_json_round_trip() {
+        let data = AnnualGcmData {
+            results: Some(vec![AnnualGcmDatum {
+                gcm: String::from("bccr_bcm2_0"),
+                variable: String::from("pr"),
+                from_year: String::from("1980"),
+                to_year: String::from("1999"),
+                scenario: None,
+                annual_data: AnnualData {
+                    double: 990.3913223140494,
+                },
+            }]),
+        };
+
+        let json = serde_json::to_string(&data).unwrap();
+        let deserialized: AnnualGcmData = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(data, deserialized);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::data::annual_gcm_data::AnnualGcmDatum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// List of per-GCM data for several climate variables returned in a single document.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename = "list")]
+pub struct CombinedClimateData {
+    /// Per-GCM results of all variables, serialized as `domain.web.AnnualGcmDatum`. Empty if the API has no data for
+    /// the requested year interval.
+    #[serde(rename = "domain.web.AnnualGcmDatum", default)]
+    pub results: Vec<AnnualGcmDatum>,
+}
+
+impl CombinedClimateData {
+    /// Groups the per-GCM results by their climate variable.
+    ///
+    /// # Returns
+    /// Map from the variable name, e.g. `pr`, to its per-GCM results in the order returned by the API.
+    pub fn by_variable(&self) -> BTreeMap<String, Vec<AnnualGcmDatum>> {
+        let mut by_variable: BTreeMap<String, Vec<AnnualGcmDatum>> = BTreeMap::new();
+
+        for datum in &self.results {
+            by_variable
+                .entry(datum.variable.clone())
+                .or_default()
+                .push(datum.clone());
+        }
+
+        by_variable
+    }
+}
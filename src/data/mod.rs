@@ -1 +1,4 @@
-pub mod annual_gcm_data;
\ No newline at end of file
+pub mod annual_ensemble_data;
+pub mod annual_gcm_data;
+pub mod combined_climate_data;
+pub mod monthly_gcm_data;
\ No newline at end of file
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Monthly values reported by a single Global Circulation Model.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MonthlyValues {
+    /// One value per month from January to December, serialized as repeated `double` elements.
+    #[serde(rename = "double", default)]
+    pub values: Vec<f64>,
+}
+
+/// Monthly data reported by a single Global Circulation Model (GCM) for a year interval.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyGcmDatum {
+    /// Name of the GCM, serialized as `gcm`.
+    pub gcm: String,
+    /// Climate variable, e.g. `pr` for precipitation, serialized as `variable`.
+    pub variable: String,
+    /// Emission scenario of future data, e.g. `a2`, serialized as `scenario`. `None` for historical data.
+    pub scenario: Option<String>,
+    /// Start of the year interval, serialized as `fromYear`.
+    pub from_year: String,
+    /// End of the year interval, serialized as `toYear`.
+    pub to_year: String,
+    /// The monthly values, serialized as `monthVals`.
+    pub month_vals: MonthlyValues,
+}
+
+/// List of per-GCM data returned by the monthly endpoints.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename = "list")]
+pub struct MonthlyGcmData {
+    /// Per-GCM results, serialized as `domain.web.MonthlyGcmDatum`. `None` if the API has no data for the requested
+    /// year interval.
+    #[serde(rename = "domain.web.MonthlyGcmDatum")]
+    pub results: Option<Vec<MonthlyGcmDatum>>,
+}
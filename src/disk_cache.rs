@@ -0,0 +1,73 @@
+use crate::atomic_file::write_atomically;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Response bodies stored on disk, one file per request URL named after its 64-bit FNV-1a hash, which unlike the
+/// standard library's hasher stays the same across Rust releases, so the cache survives toolchain upgrades.
+#[derive(Debug, Clone)]
+pub(crate) struct DiskCache {
+    directory: PathBuf,
+    ttl: Option<Duration>,
+}
+
+impl DiskCache {
+    pub(crate) fn new(directory: PathBuf, ttl: Option<Duration>) -> Self {
+        DiskCache { directory, ttl }
+    }
+
+    /// Reads the cached body of the URL. Entries older than the TTL, judged by their modification time, are treated
+    /// as missing.
+    pub(crate) fn get(&self, url: &str) -> Option<String> {
+        let path = self.path(url);
+
+        if let Some(ttl) = self.ttl {
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()?;
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+
+            if age >= ttl {
+                return None;
+            }
+        }
+
+        fs::read_to_string(path).ok()
+    }
+
+    pub(crate) fn put(&self, url: &str, body: &str) -> Result<(), io::Error> {
+        fs::create_dir_all(&self.directory)?;
+        write_atomically(&self.path(url), body)
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.directory
+            .join(format!("{:016x}.xml", fnv1a(url.as_bytes())))
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiskCache;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_cache_file_names_are_stable() {
+        let cache = DiskCache::new(PathBuf::from("cache"), None);
+
+        assert_eq!(cache.path(""), PathBuf::from("cache/cbf29ce484222325.xml"));
+        assert_eq!(cache.path("a"), PathBuf::from("cache/af63dc4c8601ec8c.xml"));
+    }
+}
@@ -4,23 +4,67 @@ use std::{fmt::Display, io};
 pub enum Error {
     DateRangeNotSupported(u16, u16),
     NotRecognizedByClimateWeb,
-    Deserialization(quick_xml::DeError),
+    ZeroAverage(u16, u16),
+    NoDataForCountry(String),
+    DeadlineExceeded,
+    NoCountries,
+    NoDefaultWindow,
+    ZeroWindowCount,
+    NonFiniteValue(String),
+    FirstCountry(String, Box<Error>),
+    SecondCountry(String, Box<Error>),
+    Deserialization(String, quick_xml::DeError),
+    Timeout(reqwest::Error),
+    Connect(reqwest::Error),
+    Tls(reqwest::Error),
     Reqwest(reqwest::Error),
     Io(io::Error),
 }
 
+/// A problem with the arguments of a request, found without contacting the API.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    StartYearNotSupported(u16),
+    SpanNotSupported(u16, u16),
+    MalformedCountryCode(String),
+}
+
 impl std::error::Error for Error {}
 
+impl std::error::Error for ValidationError {}
+
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Self {
-        Error::Reqwest(e)
+        if e.is_timeout() {
+            Error::Timeout(e)
+        } else if is_tls_error(&e) {
+            Error::Tls(e)
+        } else if e.is_connect() {
+            Error::Connect(e)
+        } else {
+            Error::Reqwest(e)
+        }
     }
 }
 
-impl From<quick_xml::DeError> for Error {
-    fn from(e: quick_xml::DeError) -> Self {
-        Error::Deserialization(e)
+/// reqwest doesn't classify TLS failures and its TLS backend's error types aren't part of its API, so the source chain
+/// is searched for a message of the TLS library instead.
+fn is_tls_error(e: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(e);
+
+    while let Some(e) = source {
+        let message = e.to_string().to_lowercase();
+        if ["tls", "ssl", "certificate", "handshake"]
+            .iter()
+            .any(|keyword| message.contains(keyword))
+        {
+            return true;
+        }
+
+        source = e.source();
     }
+
+    false
 }
 
 impl From<io::Error> for Error {
@@ -36,9 +80,59 @@ impl Display for Error {
                 write!(f, "Date range {}-{} not supported", from_date, to_date)
             }
             Error::NotRecognizedByClimateWeb => write!(f, "Not recognized by ClimateWeb"),
+            Error::ZeroAverage(from_date, to_date) => {
+                write!(f, "Average for {}-{} is zero", from_date, to_date)
+            }
+            Error::NoDataForCountry(country_iso) => {
+                write!(f, "No data for country {}", country_iso)
+            }
+            Error::DeadlineExceeded => write!(f, "Deadline exceeded"),
+            Error::NoCountries => write!(f, "No countries given"),
+            Error::NoDefaultWindow => write!(f, "No default year window configured"),
+            Error::ZeroWindowCount => write!(f, "The window count must be at least 1"),
+            Error::NonFiniteValue(gcm) => write!(f, "Non-finite value reported by {}", gcm),
+            Error::FirstCountry(country_iso, e) => {
+                write!(
+                    f,
+                    "Request for the first country {} failed: {}",
+                    country_iso, e
+                )
+            }
+            Error::SecondCountry(country_iso, e) => {
+                write!(
+                    f,
+                    "Request for the second country {} failed: {}",
+                    country_iso, e
+                )
+            }
+            Error::Timeout(e) => write!(f, "{}", e),
+            Error::Connect(e) => write!(f, "{}", e),
+            Error::Tls(e) => write!(f, "{}", e),
             Error::Reqwest(e) => write!(f, "{}", e),
-            Error::Deserialization(e) => write!(f, "{}", e),
+            Error::Deserialization(url, e) => {
+                write!(f, "Failed to parse the response from {}: {}", url, e)
+            }
             Error::Io(e) => write!(f, "{}", e),
         }
     }
 }
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::StartYearNotSupported(from_year) => {
+                write!(f, "Start year {} not supported", from_year)
+            }
+            ValidationError::SpanNotSupported(from_year, to_year) => {
+                write!(
+                    f,
+                    "Year interval {}-{} doesn't span 20 years",
+                    from_year, to_year
+                )
+            }
+            ValidationError::MalformedCountryCode(country_iso) => {
+                write!(f, "Country code {} isn't an ISO3 code", country_iso)
+            }
+        }
+    }
+}
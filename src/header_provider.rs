@@ -0,0 +1,16 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Callback invoked before each request to produce headers for that request, as `(name, value)` pairs. It may be
+/// called from several threads at once, e.g. by batch methods, so it must be `Send + Sync` and synchronize any state
+/// it keeps.
+pub type HeaderProviderFn = dyn Fn() -> Vec<(String, String)> + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct HeaderProvider(pub(crate) Arc<HeaderProviderFn>);
+
+impl fmt::Debug for HeaderProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HeaderProvider")
+    }
+}
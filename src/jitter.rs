@@ -0,0 +1,49 @@
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Randomizes retry delays so clients failing at the same time don't retry in lockstep. Uses a small seedable
+/// SplitMix64 generator, so a fixed seed yields the same sequence of delays.
+#[derive(Debug)]
+pub(crate) struct Jitter {
+    fraction: f64,
+    state: Mutex<u64>,
+}
+
+impl Jitter {
+    /// Create a jitter that shortens delays by a random part of up to `fraction` of their length. A fraction of 1
+    /// is full jitter, spreading delays uniformly between zero and the full delay.
+    pub(crate) fn new(fraction: f64, seed: u64) -> Self {
+        Jitter {
+            fraction,
+            state: Mutex::new(seed),
+        }
+    }
+
+    /// Create a jitter seeded from the current time.
+    pub(crate) fn unseeded(fraction: f64) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+
+        Jitter::new(fraction, seed)
+    }
+
+    /// Picks a random delay between `(1 - fraction) * delay` and `delay`.
+    pub(crate) fn apply(&self, delay: Duration) -> Duration {
+        delay.mul_f64(1.0 - self.fraction * self.next_unit())
+    }
+
+    /// Returns a random number in `[0, 1)`.
+    fn next_unit(&self) -> f64 {
+        let mut state = self.state.lock().unwrap();
+
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -1,6 +1,37 @@
+mod atomic_file;
 mod climate_api_client;
+mod countries;
 mod data;
+mod disk_cache;
 mod error;
+mod header_provider;
+mod jitter;
+mod markdown;
+mod rainfall;
+mod rainfall_class;
+mod request_hook;
+#[cfg(test)]
+mod stub_server;
+#[cfg(test)]
+mod temp_dir;
+mod token_bucket;
+mod unsupported_range;
+mod year_window;
 
 pub use climate_api_client::ClimateApiClient;
 pub use climate_api_client::ClimateApiClientBuilder;
+pub use climate_api_client::CountryErrorDetection;
+pub use countries::Country;
+pub use data::annual_ensemble_data::{AnnualEnsembleData, AnnualEnsembleDatum};
+pub use data::annual_gcm_data::{AnnualData, AnnualGcmData, AnnualGcmDatum};
+pub use data::combined_climate_data::CombinedClimateData;
+pub use data::monthly_gcm_data::{MonthlyGcmData, MonthlyGcmDatum, MonthlyValues};
+pub use error::{Error, ValidationError};
+pub use header_provider::HeaderProviderFn;
+pub use rainfall::Rainfall;
+pub use rainfall_class::{
+    RainfallClass, MODERATE_MIN_MM, SEMIARID_MIN_MM, VERY_WET_MIN_MM, WET_MIN_MM,
+};
+pub use request_hook::{RequestHookFn, RequestInfo};
+pub use unsupported_range::UnsupportedRangeFn;
+pub use year_window::{WindowKind, YearWindow};
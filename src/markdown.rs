@@ -0,0 +1,65 @@
+const RESPONSE_BODY_HEADING: &str = "### Response body recorded for playback";
+const CODE_FENCE: &str = "```";
+
+/// A single recorded HTTP interaction in the Servirtium markdown format.
+pub struct Interaction<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub request_headers: &'a [(String, String)],
+    pub status: u16,
+    pub response_headers: &'a [(String, String)],
+    pub response_body: &'a str,
+}
+
+/// Render the interaction as Servirtium playback markdown.
+pub fn write_interaction(index: usize, interaction: &Interaction) -> String {
+    let content_type = interaction
+        .response_headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.as_str())
+        .unwrap_or_default();
+
+    format!(
+        "## Interaction {}: {} {}\n\n\
+         ### Request headers recorded for playback:\n\n{}\n\n\
+         ### Request body recorded for playback ():\n\n{}\n\n\
+         ### Response headers recorded for playback:\n\n{}\n\n\
+         {} ({}: {}):\n\n{}\n\n",
+        index,
+        interaction.method,
+        interaction.path,
+        fenced(&write_headers(interaction.request_headers)),
+        fenced(""),
+        fenced(&write_headers(interaction.response_headers)),
+        RESPONSE_BODY_HEADING,
+        interaction.status,
+        content_type,
+        fenced(interaction.response_body.trim_end()),
+    )
+}
+
+/// Extract the response body of the first interaction in the playback markdown.
+#[cfg(test)]
+pub fn read_response_body(markdown: &str) -> Option<String> {
+    let markdown = markdown.replace('\r', "");
+    let section = &markdown[markdown.find(RESPONSE_BODY_HEADING)?..];
+    let body = &section[section.find(&format!("{}\n", CODE_FENCE))? + CODE_FENCE.len() + 1..];
+    let end = body.find(&format!("\n{}", CODE_FENCE))?;
+
+    Some(String::from(&body[..end]))
+}
+
+fn write_headers(headers: &[(String, String)]) -> String {
+    let mut lines: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect();
+
+    lines.sort();
+    lines.join("\n")
+}
+
+fn fenced(content: &str) -> String {
+    format!("{}\n{}\n{}", CODE_FENCE, content, CODE_FENCE)
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+
+/// Number of millimeters in an inch.
+const MILLIMETERS_PER_INCH: f64 = 25.4;
+
+/// Average annual rainfall carrying its unit, so values in millimeters and inches can't be mixed up.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Rainfall {
+    millimeters_per_year: f64,
+}
+
+impl Rainfall {
+    /// Create a rainfall from millimeters a year.
+    pub fn from_millimeters(millimeters_per_year: f64) -> Self {
+        Rainfall {
+            millimeters_per_year,
+        }
+    }
+
+    /// Create a rainfall from inches a year.
+    pub fn from_inches(inches_per_year: f64) -> Self {
+        Rainfall::from_millimeters(inches_per_year * MILLIMETERS_PER_INCH)
+    }
+
+    /// The rainfall in millimeters a year.
+    pub fn millimeters(&self) -> f64 {
+        self.millimeters_per_year
+    }
+
+    /// The rainfall in inches a year.
+    pub fn inches(&self) -> f64 {
+        self.millimeters_per_year / MILLIMETERS_PER_INCH
+    }
+}
+
+impl Add for Rainfall {
+    type Output = Rainfall;
+
+    fn add(self, other: Rainfall) -> Rainfall {
+        Rainfall::from_millimeters(self.millimeters_per_year + other.millimeters_per_year)
+    }
+}
+
+impl Sub for Rainfall {
+    type Output = Rainfall;
+
+    fn sub(self, other: Rainfall) -> Rainfall {
+        Rainfall::from_millimeters(self.millimeters_per_year - other.millimeters_per_year)
+    }
+}
+
+impl fmt::Display for Rainfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} mm/year", self.millimeters_per_year)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rainfall;
+
+    #[test]
+    fn test_rainfall_converts_to_inches() {
+        let rainfall = Rainfall::from_millimeters(254.0);
+
+        assert!((rainfall.inches() - 10.0).abs() < f64::EPSILON);
+        assert!((Rainfall::from_inches(10.0).millimeters() - 254.0).abs() < f64::EPSILON);
+    }
+}
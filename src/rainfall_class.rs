@@ -0,0 +1,60 @@
+/// Lower bound of `RainfallClass::Semiarid` in millimeters of average annual rainfall.
+pub const SEMIARID_MIN_MM: f64 = 250.0;
+/// Lower bound of `RainfallClass::Moderate` in millimeters of average annual rainfall.
+pub const MODERATE_MIN_MM: f64 = 500.0;
+/// Lower bound of `RainfallClass::Wet` in millimeters of average annual rainfall.
+pub const WET_MIN_MM: f64 = 800.0;
+/// Lower bound of `RainfallClass::VeryWet` in millimeters of average annual rainfall.
+pub const VERY_WET_MIN_MM: f64 = 2000.0;
+
+/// Human-readable classification of average annual rainfall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RainfallClass {
+    /// Less than 250 mm a year.
+    Arid,
+    /// From 250 mm up to 500 mm a year.
+    Semiarid,
+    /// From 500 mm up to 800 mm a year.
+    Moderate,
+    /// From 800 mm up to 2000 mm a year.
+    Wet,
+    /// 2000 mm a year or more.
+    VeryWet,
+}
+
+impl RainfallClass {
+    /// Classify an average annual rainfall.
+    ///
+    /// # Arguments
+    /// `rainfall_mm` - average annual rainfall in millimeters.
+    ///
+    /// # Returns
+    /// The class whose range contains `rainfall_mm`.
+    pub fn from_mm(rainfall_mm: f64) -> Self {
+        if rainfall_mm >= VERY_WET_MIN_MM {
+            RainfallClass::VeryWet
+        } else if rainfall_mm >= WET_MIN_MM {
+            RainfallClass::Wet
+        } else if rainfall_mm >= MODERATE_MIN_MM {
+            RainfallClass::Moderate
+        } else if rainfall_mm >= SEMIARID_MIN_MM {
+            RainfallClass::Semiarid
+        } else {
+            RainfallClass::Arid
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RainfallClass;
+
+    #[test]
+    fn test_rainfall_class_boundaries() {
+        assert_eq!(RainfallClass::from_mm(249.9), RainfallClass::Arid);
+        assert_eq!(RainfallClass::from_mm(250.0), RainfallClass::Semiarid);
+        assert_eq!(RainfallClass::from_mm(500.0), RainfallClass::Moderate);
+        assert_eq!(RainfallClass::from_mm(800.0), RainfallClass::Wet);
+        assert_eq!(RainfallClass::from_mm(2000.0), RainfallClass::VeryWet);
+    }
+}
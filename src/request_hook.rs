@@ -0,0 +1,26 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Information about a request passed to a request hook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestInfo {
+    /// URL of the request.
+    pub url: String,
+    /// Status code of the response. `None` before the request is sent or if no response was received.
+    pub status: Option<u16>,
+    /// Time taken to receive the response. `None` before the request is sent.
+    pub elapsed: Option<Duration>,
+}
+
+/// Callback invoked before each request and after each response.
+pub type RequestHookFn = dyn Fn(&RequestInfo) + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct RequestHook(pub(crate) Arc<RequestHookFn>);
+
+impl fmt::Debug for RequestHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RequestHook")
+    }
+}
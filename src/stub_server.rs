@@ -0,0 +1,128 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const REQUEST_HEAD_END: &[u8] = b"\r\n\r\n";
+
+/// Minimal HTTP server used by tests that need full control over the upstream responses.
+pub struct StubServer {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl StubServer {
+    /// Start a server on a random local port. Every connection is served on its own thread with the raw response
+    /// produced by `handler` from the raw request head. Connections are closed after a single response.
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handler = Arc::new(handler);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let server_requests = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let handler = Arc::clone(&handler);
+                let requests = Arc::clone(&server_requests);
+
+                thread::spawn(move || handle_connection(stream, &*handler, &requests));
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:12345`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Raw heads of all requests received so far, in the order they arrived.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn handle_connection<F: Fn(&str) -> String>(
+    mut stream: TcpStream,
+    handler: &F,
+    requests: &Mutex<Vec<String>>,
+) {
+    let request = match read_request_head(&mut stream) {
+        Some(request) => request,
+        None => return,
+    };
+
+    requests.lock().unwrap().push(request.clone());
+
+    let response = handler(&request);
+
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
+
+fn read_request_head(stream: &mut TcpStream) -> Option<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+
+    while !head
+        .windows(REQUEST_HEAD_END.len())
+        .any(|window| window == REQUEST_HEAD_END)
+    {
+        match stream.read(&mut buffer) {
+            Ok(0) | Err(_) => return None,
+            Ok(read) => head.extend_from_slice(&buffer[..read]),
+        }
+    }
+
+    Some(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// Path of the request, taken from the request line.
+pub fn request_path(request: &str) -> &str {
+    request.split_whitespace().nth(1).unwrap_or_default()
+}
+
+/// Build a raw HTTP/1.1 response that closes the connection.
+pub fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!(
+        "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n",
+        status,
+        body.len()
+    );
+
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+
+    response.push_str("\r\n");
+    response.push_str(body);
+    response
+}
+
+/// Build a `200 OK` response with an XML body.
+pub fn xml_response(body: &str) -> String {
+    response("200 OK", &[("content-type", "application/xml")], body)
+}
+
+/// Build an annual average response body with one GCM per given value.
+pub fn annual_gcm_xml(values: &[f64]) -> String {
+    let data: String = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            format!(
+                "<domain.web.AnnualGcmDatum><gcm>gcm_{}</gcm><variable>pr</variable>\
+                 <fromYear>1980</fromYear><toYear>1999</toYear>\
+                 <annualData><double>{}</double></annualData></domain.web.AnnualGcmDatum>",
+                i, value
+            )
+        })
+        .collect();
+
+    format!("<list>{}</list>", data)
+}
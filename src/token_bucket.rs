@@ -0,0 +1,88 @@
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Token bucket refilled at a constant rate, used both to limit the request rate and to cap retries.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    tokens_per_second: f64,
+    capacity: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket holding up to `capacity` tokens.
+    pub(crate) fn new(tokens_per_second: f64, capacity: u32) -> Self {
+        TokenBucket {
+            tokens_per_second,
+            capacity: f64::from(capacity),
+            state: Mutex::new(BucketState {
+                tokens: f64::from(capacity),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token, blocking until one is available. A caller that finds the bucket empty reserves the next token,
+    /// so concurrent callers are queued rather than woken at the same time. The refill rate must be positive.
+    ///
+    /// # Arguments
+    /// `deadline` - if the next token isn't available before this point in time, no token is taken and the call
+    ///     returns immediately.
+    ///
+    /// # Returns
+    /// `true` if a token was taken.
+    pub(crate) fn acquire_before(&self, deadline: Option<Instant>) -> bool {
+        let wait = {
+            let mut state = self.refill();
+
+            let wait = if state.tokens >= 1.0 {
+                Duration::from_secs(0)
+            } else {
+                Duration::from_secs_f64((1.0 - state.tokens) / self.tokens_per_second)
+            };
+
+            if deadline.is_some_and(|deadline| state.last_refill + wait > deadline) {
+                return false;
+            }
+
+            state.tokens -= 1.0;
+            wait
+        };
+
+        thread::sleep(wait);
+        true
+    }
+
+    /// Takes a token if one is available without blocking.
+    ///
+    /// # Returns
+    /// `true` if a token was taken.
+    pub(crate) fn try_acquire(&self) -> bool {
+        let mut state = self.refill();
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn refill(&self) -> MutexGuard<'_, BucketState> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let refilled = now.duration_since(state.last_refill).as_secs_f64() * self.tokens_per_second;
+
+        state.tokens = (state.tokens + refilled).min(self.capacity);
+        state.last_refill = now;
+
+        state
+    }
+}
@@ -0,0 +1,15 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Predicate deciding from the response body and status code whether a response means that the requested year
+/// interval isn't supported. For error status codes the body isn't available and an empty string is passed.
+pub type UnsupportedRangeFn = dyn Fn(&str, u16) -> bool + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct UnsupportedRange(pub(crate) Arc<UnsupportedRangeFn>);
+
+impl fmt::Debug for UnsupportedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UnsupportedRange")
+    }
+}
@@ -0,0 +1,127 @@
+use crate::error::Error;
+
+const FIRST_HISTORICAL_FROM_YEAR: u16 = 1920;
+const LAST_HISTORICAL_FROM_YEAR: u16 = 1980;
+const FIRST_FUTURE_FROM_YEAR: u16 = 2020;
+const LAST_FUTURE_FROM_YEAR: u16 = 2080;
+const FROM_YEAR_STEP: u16 = 20;
+const TO_YEAR_OFFSET: u16 = 19;
+
+/// Whether a year window covers historical (observed) or future (modeled) data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowKind {
+    /// One of the windows between 1920-1939 and 1980-1999.
+    Historical,
+    /// One of the windows between 2020-2039 and 2080-2099.
+    Future,
+}
+
+/// A 20-year interval supported by the WorldBank Climate Data API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct YearWindow {
+    from: u16,
+    to: u16,
+}
+
+impl YearWindow {
+    /// Create a YearWindow starting at the given year.
+    ///
+    /// # Arguments
+    /// `from` - start of the year interval. It should be divisible by 20 and either between 1920 and 1980 inclusive
+    ///     (historical) or between 2020 and 2080 inclusive (future).
+    ///
+    /// # Returns
+    /// A YearWindow ending at `from` + 19 or `Error::DateRangeNotSupported` if `from` isn't a supported start year.
+    pub fn new(from: u16) -> Result<Self, Error> {
+        let to = from.saturating_add(TO_YEAR_OFFSET);
+        let in_supported_range = (FIRST_HISTORICAL_FROM_YEAR..=LAST_HISTORICAL_FROM_YEAR)
+            .contains(&from)
+            || (FIRST_FUTURE_FROM_YEAR..=LAST_FUTURE_FROM_YEAR).contains(&from);
+
+        if !in_supported_range || from % FROM_YEAR_STEP != 0 {
+            return Err(Error::DateRangeNotSupported(from, to));
+        }
+
+        Ok(Self { from, to })
+    }
+
+    /// All historical windows, from 1920-1939 to 1980-1999, in chronological order.
+    pub fn historical() -> Vec<Self> {
+        (FIRST_HISTORICAL_FROM_YEAR..=LAST_HISTORICAL_FROM_YEAR)
+            .step_by(usize::from(FROM_YEAR_STEP))
+            .map(|from| Self {
+                from,
+                to: from + TO_YEAR_OFFSET,
+            })
+            .collect()
+    }
+
+    /// All future windows, from 2020-2039 to 2080-2099, in chronological order.
+    pub fn future() -> Vec<Self> {
+        (FIRST_FUTURE_FROM_YEAR..=LAST_FUTURE_FROM_YEAR)
+            .step_by(usize::from(FROM_YEAR_STEP))
+            .map(|from| Self {
+                from,
+                to: from + TO_YEAR_OFFSET,
+            })
+            .collect()
+    }
+
+    /// Start of the year interval.
+    pub fn from(&self) -> u16 {
+        self.from
+    }
+
+    /// End of the year interval.
+    pub fn to(&self) -> u16 {
+        self.to
+    }
+
+    /// Whether the window covers historical or future data.
+    pub fn kind(&self) -> WindowKind {
+        if self.from <= LAST_HISTORICAL_FROM_YEAR {
+            WindowKind::Historical
+        } else {
+            WindowKind::Future
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WindowKind, YearWindow};
+    use crate::error::Error;
+
+    #[test]
+    fn test_valid_year_window() {
+        let window = YearWindow::new(1980).unwrap();
+
+        assert_eq!(window.from(), 1980);
+        assert_eq!(window.to(), 1999);
+    }
+
+    #[test]
+    fn test_year_window_rejects_start_year_not_divisible_by_20() {
+        match YearWindow::new(1985) {
+            Err(Error::DateRangeNotSupported(1985, 2004)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_year_window_rejects_start_year_between_historical_and_future() {
+        match YearWindow::new(2000) {
+            Err(Error::DateRangeNotSupported(2000, 2019)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_year_window_kind() {
+        assert_eq!(
+            YearWindow::new(1980).unwrap().kind(),
+            WindowKind::Historical
+        );
+        assert_eq!(YearWindow::new(2020).unwrap().kind(), WindowKind::Future);
+    }
+}